@@ -1,3 +1,5 @@
+#![allow(clippy::unit_arg, clippy::disallowed_names)]
+
 use std::{fmt::{self, Display, Write}, hint::black_box};
 
 use criterion::{criterion_group, criterion_main, Criterion};
@@ -1,3 +1,5 @@
+#![allow(clippy::unit_arg, clippy::disallowed_names)]
+
 use std::{fmt::{self, Alignment, Display, Write}, hint::black_box, thread::spawn, time::Instant};
 
 struct NullWriter;
@@ -0,0 +1,176 @@
+//! Case transforms of the whole output
+
+use core::fmt;
+
+use crate::FormatterBuilder;
+
+/// Case of the whole output, used by [`FormatterBuilder::case`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Case {
+    /// Like [`str::to_uppercase`], e.g `ß` into `SS`
+    Upper,
+    /// Like [`str::to_lowercase`], with final sigma `ς`
+    Lower,
+    /// First letter of words in titlecase, others in lowercase
+    Title,
+}
+
+impl FormatterBuilder<'_> {
+    /// Transform the case of everything the closure writes, before padding
+    ///
+    /// Case mappings are Unicode, a char can map into multiple chars
+    ///
+    /// Padding before a closure output longer than 1024 bytes is not written, the output is streamed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::Debug;
+    /// #[derive(Debug)]
+    /// enum Method { Get }
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().case(Case::Upper).width(5).with(&mut writter, |f| {
+    ///     Method::Get.fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "GET  ");
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().case(Case::Title).with(&mut writter, |f| {
+    ///     f.write_str("hello wORLD, straße")
+    /// }).unwrap();
+    /// assert_eq!(writter, "Hello World, Straße");
+    /// ```
+    pub fn case(&mut self, case: impl Into<Option<Case>>) -> &mut Self {
+        self.case = case.into();
+        self
+    }
+}
+
+/// Titlecase of the digraphs, others are uppercase
+fn titlecase(ch: char) -> Option<char> {
+    Some(match ch {
+        'Ǆ' | 'ǅ' | 'ǆ' => 'ǅ',
+        'Ǉ' | 'ǈ' | 'ǉ' => 'ǈ',
+        'Ǌ' | 'ǋ' | 'ǌ' => 'ǋ',
+        'Ǳ' | 'ǲ' | 'ǳ' => 'ǲ',
+        _ => return None,
+    })
+}
+
+/// Char in a word of [`Case::Title`]
+fn is_word(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '\'' | '’')
+}
+
+/// Streaming case mapping
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct CaseMap {
+    case: Case,
+    /// Previous char is a letter, for final sigma
+    after_letter: bool,
+    /// Previous char is in a word, for title
+    in_word: bool,
+    /// `Σ` in lowercase, final or not by the next char
+    pending_sigma: bool,
+}
+
+impl CaseMap {
+    pub(crate) fn new(case: Case) -> Self {
+        Self { case, after_letter: false, in_word: false, pending_sigma: false }
+    }
+
+    /// Map the char, the mapped chars are written by `out`
+    pub(crate) fn push(
+        &mut self,
+        ch: char,
+        mut out: impl FnMut(char) -> fmt::Result,
+    ) -> fmt::Result {
+        if self.pending_sigma {
+            self.pending_sigma = false;
+            out(if ch.is_alphabetic() { 'σ' } else { 'ς' })?;
+        }
+
+        let lower = match self.case {
+            Case::Upper => false,
+            Case::Lower => true,
+            Case::Title => self.in_word,
+        };
+        let after_letter = self.after_letter;
+        self.after_letter = ch.is_alphabetic();
+        self.in_word = is_word(ch);
+
+        match (lower, ch) {
+            (true, 'Σ') if after_letter => {
+                self.pending_sigma = true;
+                Ok(())
+            },
+            (true, _) => ch.to_lowercase().try_for_each(out),
+            (false, _) if self.case == Case::Title => match titlecase(ch) {
+                Some(title) => out(title),
+                // like `ﬁ` into `Fi`
+                None => ch.to_uppercase().enumerate().try_for_each(|(i, upper)| match i {
+                    0 => out(upper),
+                    _ => upper.to_lowercase().try_for_each(&mut out),
+                }),
+            },
+            (false, _) => ch.to_uppercase().try_for_each(out),
+        }
+    }
+
+    /// End of output, write the pending final sigma
+    pub(crate) fn finish(&mut self, mut out: impl FnMut(char) -> fmt::Result) -> fmt::Result {
+        if self.pending_sigma {
+            self.pending_sigma = false;
+            out('ς')?;
+        }
+        self.after_letter = false;
+        self.in_word = false;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Alignment;
+
+    fn run(b: &FormatterBuilder<'_>, s: &str) -> String {
+        let mut out = String::new();
+        b.with(&mut out, |f| f.write_str(s)).unwrap();
+        out
+    }
+
+    #[test]
+    fn cases() {
+        let mut b = FormatterBuilder::new();
+        b.case(Case::Upper);
+        assert_eq!(run(&b, "straße ǆ ﬁ"), "STRASSE Ǆ FI");
+        assert_eq!(run(&b, "0xff 1e5"), "0XFF 1E5");
+
+        b.case(Case::Lower);
+        assert_eq!(run(&b, "ὈΔΥΣΣΕΎΣ ΣΑΣ Σ"), "ὀδυσσεύς σας σ");
+        assert_eq!(run(&b, "İ"), "i\u{307}");
+
+        b.case(Case::Title);
+        assert_eq!(run(&b, "ǆungla o'neil ΣΑΣ"), "ǅungla O'neil Σας");
+        assert_eq!(run(&b, "ﬁre 2nd"), "Fire 2nd");
+    }
+
+    #[test]
+    fn padding() {
+        let mut b = FormatterBuilder::new();
+        b.case(Case::Upper).width(8).align(Alignment::Right);
+        assert_eq!(run(&b, "ß"), "      SS");
+        assert_eq!(run(&b, &"ß".repeat(200)), "SS".repeat(200));
+        assert_eq!(run(b.width(300), &"a".repeat(280)), " ".repeat(20) + &"A".repeat(280));
+        // longer than the capture buffer, padding before it is not written
+        assert_eq!(run(b.width(2000), &"a".repeat(1100)), "A".repeat(1100));
+        b.width(8);
+
+        let mut out = String::new();
+        b.grouping(',', 3).with(&mut out, |f| write!(f, "{} x", 1234)).unwrap();
+        assert_eq!(out, " 1,234 X");
+    }
+}
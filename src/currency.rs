@@ -0,0 +1,235 @@
+//! Currency adapter, like `$1,234.50` `1.234,50 €` `(1,234.50)`
+
+use core::fmt::{self, Display, Formatter, Write};
+
+use crate::{
+    number::{Pad, PadAlign},
+    post::{self, Count, Sink},
+    Decimal, FormatterBuilder,
+};
+
+/// Where the currency symbol is written
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymbolPosition {
+    /// Before the amount, like `$1.00`
+    #[default]
+    Prefix,
+    /// After the amount, like `1,00 €`
+    Suffix,
+}
+
+/// Output of negative amounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NegativeStyle {
+    /// Leading minus, like `-$1.00`
+    #[default]
+    Minus,
+    /// Accounting, like `($1.00)`
+    Parentheses,
+    /// Trailing minus, like `$1.00-`
+    TrailingMinus,
+}
+
+/// Format a [`Decimal`] amount with a currency symbol
+///
+/// - Precision overrides the [`decimals`](Currency::decimals), default is 2
+/// - Sign plus writes `+` on positive amounts
+/// - Width, fill and align apply to the whole amount, default is right-aligned
+/// - Sign aware zero pad inserts zeros between the symbol and the digits
+/// - Amounts rounded to zero are not negative
+///
+/// Use [`FormatterBuilder::currency`] for grouping and locale
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// let amount = Decimal::new(-123450, 2);
+/// assert_eq!(format!("{}", Currency::new(amount, "$")), "-$1234.50");
+/// assert_eq!(format!("{}", Currency::new(amount, "$").negative(NegativeStyle::Parentheses)), "($1234.50)");
+/// assert_eq!(format!("{:.0}", Currency::new(amount, "$").negative(NegativeStyle::TrailingMinus)), "$1234-");
+/// assert_eq!(format!("{:>12}", Currency::new(amount, "€").position(SymbolPosition::Suffix).space(true)), "  -1234.50 €");
+/// assert_eq!(format!("{:+010}", Currency::new(Decimal::new(5, 0), "$")), "+$00005.00");
+/// assert_eq!(format!("{}", Currency::new(Decimal::new(1234, 0), "¥").decimals(0)), "¥1234");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Currency<'a> {
+    amount: Decimal,
+    symbol: &'a str,
+    position: SymbolPosition,
+    space: bool,
+    negative: NegativeStyle,
+    decimals: u16,
+}
+
+impl<'a> Currency<'a> {
+    pub fn new(amount: Decimal, symbol: &'a str) -> Self {
+        Self {
+            amount,
+            symbol,
+            position: SymbolPosition::Prefix,
+            space: false,
+            negative: NegativeStyle::Minus,
+            decimals: 2,
+        }
+    }
+
+    /// Symbol before or after the amount
+    pub fn position(mut self, position: SymbolPosition) -> Self {
+        self.position = position;
+        self
+    }
+
+    /// Space between the symbol and the amount, like `1,00 €` `USD 1.00`
+    pub fn space(mut self, space: bool) -> Self {
+        self.space = space;
+        self
+    }
+
+    /// Output of negative amounts
+    pub fn negative(mut self, negative: NegativeStyle) -> Self {
+        self.negative = negative;
+        self
+    }
+
+    /// Fraction digits when no precision
+    pub fn decimals(mut self, decimals: u16) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    pub fn amount(&self) -> Decimal {
+        self.amount
+    }
+
+    pub fn symbol(&self) -> &'a str {
+        self.symbol
+    }
+}
+
+impl Display for Currency<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let precision = Some(f.precision().unwrap_or(self.decimals.into()));
+        let negative = self.amount.mantissa < 0 && !self.amount.is_zero(precision);
+
+        let (open, close) = match (negative, self.negative) {
+            (false, _) => (if f.sign_plus() { "+" } else { "" }, ""),
+            (true, NegativeStyle::Minus) => ("-", ""),
+            (true, NegativeStyle::Parentheses) => ("(", ")"),
+            (true, NegativeStyle::TrailingMinus) => ("", "-"),
+        };
+        let space = if self.space { " " } else { "" };
+        let (before, after) = match self.position {
+            SymbolPosition::Prefix => ([self.symbol, space], ["", ""]),
+            SymbolPosition::Suffix => (["", ""], [space, self.symbol]),
+        };
+
+        let mut count = Count { inner: Sink, chars: 0 };
+        self.amount.write_magnitude(&mut count, precision)?;
+        let len = [open, close].into_iter().chain(before).chain(after)
+            .map(|s| s.chars().count())
+            .sum::<usize>() + count.chars;
+
+        let pad = Pad::from_formatter(f, PadAlign::Right);
+        let (pre, post) = pad.split(len);
+        let (pre, mid) = match pad.align {
+            PadAlign::AfterSign => (0, pre),
+            _ => (pre, 0),
+        };
+
+        pad.write_fill(f, pre)?;
+        f.write_str(open)?;
+        before.iter().try_for_each(|s| f.write_str(s))?;
+        pad.write_fill(f, mid)?;
+        self.amount.write_magnitude(f, precision)?;
+        after.iter().try_for_each(|s| f.write_str(s))?;
+        f.write_str(close)?;
+        pad.write_fill(f, post)
+    }
+}
+
+/// Head of the currency output before the first digit
+fn digits_start(s: &str) -> usize {
+    s.find(|ch: char| ch.is_ascii_digit()).unwrap_or(0)
+}
+
+impl FormatterBuilder<'_> {
+    /// Format a [`Currency`], grouping and locale rewrite the amount
+    ///
+    /// Sign, precision, width, fill, align and post-processing are applied like [`with`](FormatterBuilder::with),
+    /// but right-aligned by default and zeros of sign aware zero pad are inserted before the digits
+    ///
+    /// Padding before an output longer than 1024 bytes is not written, the output is streamed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().grouping(',', 3).width(12).currency(&mut writter, &Currency::new(Decimal::new(-123450, 2), "$")
+    ///     .negative(NegativeStyle::Parentheses)).unwrap();
+    /// assert_eq!(writter, " ($1,234.50)");
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().locale(Locale::DE_DE).currency(&mut writter, &Currency::new(Decimal::new(123450, 2), "€")
+    ///     .position(SymbolPosition::Suffix)
+    ///     .space(true)).unwrap();
+    /// assert_eq!(writter, "1.234,50 €");
+    /// ```
+    pub fn currency<W: Write>(&self, writer: W, value: &Currency<'_>) -> fmt::Result {
+        post::with_head(self, writer, digits_start, |f| value.fmt(f))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Alignment, Sign};
+
+    fn run(b: &FormatterBuilder<'_>, value: Currency<'_>) -> String {
+        let mut out = String::new();
+        b.currency(&mut out, &value).unwrap();
+        out
+    }
+
+    #[test]
+    fn styles() {
+        let amount = Decimal::new(-1234567, 3);
+        let cases = [
+            (NegativeStyle::Minus, SymbolPosition::Prefix, false, "-$1234.57"),
+            (NegativeStyle::Minus, SymbolPosition::Suffix, true, "-1234.57 $"),
+            (NegativeStyle::Parentheses, SymbolPosition::Prefix, true, "($ 1234.57)"),
+            (NegativeStyle::Parentheses, SymbolPosition::Suffix, false, "(1234.57$)"),
+            (NegativeStyle::TrailingMinus, SymbolPosition::Prefix, false, "$1234.57-"),
+            (NegativeStyle::TrailingMinus, SymbolPosition::Suffix, true, "1234.57 $-"),
+        ];
+        for (negative, position, space, exp) in cases {
+            let currency = Currency::new(amount, "$").negative(negative).position(position).space(space);
+            assert_eq!(currency.to_string(), exp);
+        }
+        assert_eq!(Currency::new(Decimal::new(-4, 3), "$").to_string(), "$0.00");
+        assert_eq!(format!("{:.3}", Currency::new(Decimal::new(-4, 3), "$")), "-$0.004");
+        assert_eq!(Currency::new(Decimal::new(7, 0), "").to_string(), "7.00");
+    }
+
+    #[test]
+    fn padding() {
+        let currency = Currency::new(Decimal::new(-1250, 2), "$").negative(NegativeStyle::Parentheses);
+        assert_eq!(format!("{currency:10}"), "  ($12.50)");
+        assert_eq!(format!("{currency:*<10}"), "($12.50)**");
+        assert_eq!(format!("{currency:010}"), "($0012.50)");
+
+        let mut b = FormatterBuilder::new();
+        b.grouping(',', 3).sign_aware_zero_pad(true).width(12);
+        assert_eq!(run(&b, currency), "($00,012.50)");
+        assert_eq!(run(&b, Currency::new(Decimal::new(5, 0), "€").position(SymbolPosition::Suffix)), "0,000,005.00€");
+        assert_eq!(run(b.sign_aware_zero_pad(false).align(Alignment::Center), currency), "  ($12.50)  ");
+        assert_eq!(run(b.align(Alignment::Right).sign(Sign::Space), Currency::new(Decimal::new(1, 0), "$")), "       $1.00");
+        assert_eq!(run(&b, Currency::new(Decimal::new(1234, 0), "$").decimals(0)), "      $1,234");
+
+        let mut b = FormatterBuilder::new();
+        b.grouping(',', 3);
+        assert_eq!(run(&b, Currency::new(Decimal::new(123456, 2), "EUR")), "EUR1,234.56");
+        assert_eq!(run(b.sign_aware_zero_pad(true).width(12), Currency::new(Decimal::new(5, 0), "EUR")), "EUR00,005.00");
+    }
+}
@@ -0,0 +1,171 @@
+//! Fixed-point decimal adapter, like `i64` cents
+
+use core::fmt::{self, Display, Formatter, Write};
+
+use crate::number::{Measure, Pad, PadAlign};
+
+/// Format the scaled integer `mantissa / 10^scale` exactly
+///
+/// - Precision rounds the fraction half to even, or pads it with zeros,
+///   default is all `scale` digits
+/// - Sign, sign aware zero pad, width, fill and align are like floats
+/// - Grouping and locale of [`FormatterBuilder`](crate::FormatterBuilder) rewrite the output
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// use std::fmt::Display;
+/// assert_eq!(format!("{}", Decimal::new(123456, 2)), "1234.56");
+/// assert_eq!(format!("{:.1}", Decimal::new(-125, 2)), "-1.2");
+/// assert_eq!(format!("{:+.4}", Decimal::new(5, 1)), "+0.5000");
+/// assert_eq!(format!("{:08.2}", Decimal::new(-7, 3)), "-0000.01");
+///
+/// let mut writter = String::new();
+/// FormatterBuilder::new().grouping(',', 3).with(&mut writter, |f| {
+///     Decimal::new(i128::MAX, 18).fmt(f)
+/// }).unwrap();
+/// assert_eq!(writter, "170,141,183,460,469,231,731.687303715884105727");
+///
+/// let mut writter = String::new();
+/// FormatterBuilder::new().locale(Locale::DE_DE).precision(2).with(&mut writter, |f| {
+///     Decimal::new(1234567u64, 3).fmt(f)
+/// }).unwrap();
+/// assert_eq!(writter, "1.234,57");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Decimal {
+    pub mantissa: i128,
+    pub scale: u32,
+}
+
+impl Decimal {
+    pub fn new(mantissa: impl Into<i128>, scale: u32) -> Self {
+        Self { mantissa: mantissa.into(), scale }
+    }
+
+    /// Magnitude and scale rounded to precision, the scale is not larger than precision
+    fn rounded(&self, precision: Option<usize>) -> (u128, u32) {
+        let magnitude = self.mantissa.unsigned_abs();
+        let Some(drop) = precision.and_then(|precision| {
+            self.scale.checked_sub(u32::try_from(precision).ok()?).filter(|&drop| drop != 0)
+        }) else {
+            return (magnitude, self.scale);
+        };
+        let scale = self.scale - drop;
+
+        // 10^39 is larger than u128::MAX, all digits are less than half
+        let Some(pow) = 10u128.checked_pow(drop) else {
+            return (0, scale);
+        };
+        let (q, r) = (magnitude / pow, magnitude % pow);
+        let half = pow / 2;
+        let round_up = r > half || r == half && q % 2 == 1;
+        (q + u128::from(round_up), scale)
+    }
+
+    /// The magnitude rounded to precision is zero
+    pub(crate) fn is_zero(&self, precision: Option<usize>) -> bool {
+        self.rounded(precision).0 == 0
+    }
+
+    /// Write the magnitude rounded to precision, without sign
+    pub(crate) fn write_magnitude(&self, w: &mut dyn Write, precision: Option<usize>) -> fmt::Result {
+        let (magnitude, scale) = self.rounded(precision);
+        let pad_zeros = precision.map_or(0, |precision| precision.saturating_sub(scale as usize));
+
+        let mut buf = [0; 39];
+        let mut start = buf.len();
+        let mut n = magnitude;
+        loop {
+            start -= 1;
+            buf[start] = b'0' + (n % 10) as u8;
+            n /= 10;
+            if n == 0 {
+                break;
+            }
+        }
+        let digits = core::str::from_utf8(&buf[start..]).unwrap();
+        let (int, lead_zeros, frac) = match digits.len().checked_sub(scale as usize) {
+            Some(split) if split != 0 => (&digits[..split], 0, &digits[split..]),
+            _ => ("0", scale as usize - digits.len(), digits),
+        };
+
+        w.write_str(int)?;
+        if lead_zeros + frac.len() + pad_zeros != 0 {
+            w.write_char('.')?;
+        }
+        (0..lead_zeros).try_for_each(|_| w.write_char('0'))?;
+        w.write_str(frac)?;
+        (0..pad_zeros).try_for_each(|_| w.write_char('0'))
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let sign = match (self.mantissa < 0, f.sign_plus()) {
+            (true, _) => "-",
+            (false, true) => "+",
+            (false, false) => "",
+        };
+        let precision = f.precision();
+        let pad = Pad::from_formatter(f, PadAlign::Right);
+        let body = |w: &mut dyn Write| self.write_magnitude(w, precision);
+
+        Measure::new(10, &body)?.write(f, sign, "", None, &pad, &body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FormatterBuilder, Sign};
+
+    #[test]
+    fn exact() {
+        assert_eq!(Decimal::new(0, 0).to_string(), "0");
+        assert_eq!(Decimal::new(0, 2).to_string(), "0.00");
+        assert_eq!(Decimal::new(5, 0).to_string(), "5");
+        assert_eq!(Decimal::new(5, 3).to_string(), "0.005");
+        assert_eq!(Decimal::new(-105, 2).to_string(), "-1.05");
+        assert_eq!(Decimal::new(i128::MIN, 38).to_string(), "-1.70141183460469231731687303715884105728");
+        assert_eq!(Decimal::new(1, 40).to_string(), format!("0.{}1", "0".repeat(39)));
+    }
+
+    #[test]
+    fn precision() {
+        let cases = [
+            (125, 2, 1, "1.2"),
+            (135, 2, 1, "1.4"),
+            (1251, 3, 1, "1.3"),
+            (-5, 1, 0, "-0"),
+            (-15, 1, 0, "-2"),
+            (999, 2, 1, "10.0"),
+            (12, 0, 2, "12.00"),
+            (1, 40, 2, "0.00"),
+            (i128::MAX, 39, 0, "0"),
+            (i128::MAX, 38, 0, "2"),
+        ];
+        for (mantissa, scale, precision, exp) in cases {
+            assert_eq!(format!("{:.precision$}", Decimal::new(mantissa, scale)), exp, "{mantissa} {scale}");
+        }
+    }
+
+    #[test]
+    fn padding() {
+        let decimal = Decimal::new(-1234, 2);
+        assert_eq!(format!("{decimal:010}"), "-000012.34");
+        assert_eq!(format!("{decimal:<8}"), "-12.34  ");
+        assert_eq!(format!("{decimal:*^10.1}"), "**-12.3***");
+
+        let mut out = String::new();
+        FormatterBuilder::new()
+            .sign(Sign::Space)
+            .grouping(',', 3)
+            .sign_aware_zero_pad(true)
+            .width(10)
+            .with(&mut out, |f| Decimal::new(123456, 1).fmt(f))
+            .unwrap();
+        assert_eq!(out, " 012,345.6");
+    }
+}
@@ -0,0 +1,209 @@
+//! Duration formatting adapter, like `1m 3.2s` `01:03.200` `PT1M3.2S`
+
+use core::{fmt::{self, Display, Formatter, Write}, time::Duration};
+
+use crate::{
+    number::{Pad, PadAlign},
+    post::{Count, Sink},
+};
+
+/// Style of [`DurationFmt`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DurationStyle {
+    /// Nonzero units, like `1d 2h`, `1m 3.2s`, `0.5s`
+    #[default]
+    Compact,
+    /// Like `01:03.200`, and `1:02:03` when there are hours
+    Clock,
+    /// ISO 8601 duration, like `PT1M3.2S`, `P1DT2H`
+    Iso8601,
+}
+
+/// Format [`Duration`] in [`DurationStyle`]
+///
+/// Precision is the sub-second digits, rounded half up,
+/// default is the shortest digits without trailing zeros.
+/// Width, fill and align pad the whole duration
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// use core::time::Duration;
+/// use std::fmt::Display;
+/// let duration = Duration::from_millis(63_200);
+/// assert_eq!(format!("{}", DurationFmt(duration, DurationStyle::Compact)), "1m 3.2s");
+/// assert_eq!(format!("{:.3}", DurationFmt(duration, DurationStyle::Clock)), "01:03.200");
+/// assert_eq!(format!("{}", DurationFmt(duration, DurationStyle::Iso8601)), "PT1M3.2S");
+///
+/// let mut writter = String::new();
+/// FormatterBuilder::new().width(8).precision(0).with(&mut writter, |f| {
+///     DurationFmt(duration, DurationStyle::Compact).fmt(f)
+/// }).unwrap();
+/// assert_eq!(writter, "   1m 3s");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurationFmt(pub Duration, pub DurationStyle);
+
+impl DurationFmt {
+    fn write(&self, w: &mut dyn Write, precision: Option<usize>) -> fmt::Result {
+        let mut nanos = self.0.as_nanos();
+        if let Some(precision) = precision.filter(|&precision| precision < 9) {
+            let unit = 10u128.pow(9 - precision as u32);
+            nanos = (nanos + unit / 2) / unit * unit;
+        }
+
+        let fraction = Fraction { nanos: (nanos % 1_000_000_000) as u32, precision };
+        let secs = nanos / 1_000_000_000;
+        let (days, hours) = (secs / 86400, secs / 3600 % 24);
+        let (minutes, seconds) = (secs / 60 % 60, secs % 60);
+        let has_seconds = seconds != 0 || fraction.nanos != 0;
+
+        match self.1 {
+            DurationStyle::Compact => {
+                let mut sep = "";
+                for (value, unit) in [(days, 'd'), (hours, 'h'), (minutes, 'm')] {
+                    if value != 0 {
+                        write!(w, "{sep}{value}{unit}")?;
+                        sep = " ";
+                    }
+                }
+                if has_seconds || sep.is_empty() {
+                    write!(w, "{sep}{seconds}{fraction}s")?;
+                }
+                Ok(())
+            },
+            DurationStyle::Clock => {
+                let hours = secs / 3600;
+                if hours != 0 {
+                    write!(w, "{hours}:")?;
+                }
+                write!(w, "{minutes:02}:{seconds:02}{fraction}")
+            },
+            DurationStyle::Iso8601 => {
+                w.write_char('P')?;
+                if days != 0 {
+                    write!(w, "{days}D")?;
+                }
+                if hours != 0 || minutes != 0 || has_seconds || days == 0 {
+                    w.write_char('T')?;
+                }
+                for (value, unit) in [(hours, 'H'), (minutes, 'M')] {
+                    if value != 0 {
+                        write!(w, "{value}{unit}")?;
+                    }
+                }
+                if has_seconds || secs == 0 {
+                    write!(w, "{seconds}{fraction}S")?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl Display for DurationFmt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let pad = Pad::from_formatter(f, PadAlign::Right);
+        let precision = f.precision();
+        let mut count = Count { inner: Sink, chars: 0 };
+        self.write(&mut count, precision)?;
+
+        let (pre, post) = pad.split(count.chars);
+        pad.write_fill(f, pre)?;
+        self.write(f, precision)?;
+        pad.write_fill(f, post)
+    }
+}
+
+/// Sub-second digits with the point, empty when no digits
+struct Fraction {
+    nanos: u32,
+    precision: Option<usize>,
+}
+
+impl Fraction {
+    fn digits(&self) -> usize {
+        self.precision.unwrap_or_else(|| {
+            let mut nanos = self.nanos;
+            let mut digits = 9;
+            while nanos != 0 && nanos.is_multiple_of(10) {
+                nanos /= 10;
+                digits -= 1;
+            }
+            if nanos == 0 { 0 } else { digits }
+        })
+    }
+}
+
+impl Display for Fraction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let digits = self.digits();
+        if digits == 0 {
+            return Ok(());
+        }
+        f.write_char('.')?;
+
+        let mut nanos = self.nanos;
+        for i in 0..digits {
+            let digit = if i < 9 { nanos / 100_000_000 } else { 0 };
+            nanos = nanos % 100_000_000 * 10;
+            f.write_char(char::from(b'0' + digit as u8))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(style: DurationStyle, secs: u64, nanos: u32, precision: Option<usize>) -> String {
+        let duration = DurationFmt(Duration::new(secs, nanos), style);
+        match precision {
+            Some(precision) => format!("{duration:.precision$}"),
+            None => format!("{duration}"),
+        }
+    }
+
+    #[test]
+    fn compact() {
+        use DurationStyle::Compact;
+        assert_eq!(run(Compact, 0, 0, None), "0s");
+        assert_eq!(run(Compact, 0, 500_000_000, None), "0.5s");
+        assert_eq!(run(Compact, 0, 1, None), "0.000000001s");
+        assert_eq!(run(Compact, 3600, 0, None), "1h");
+        assert_eq!(run(Compact, 93784, 0, None), "1d 2h 3m 4s");
+        assert_eq!(run(Compact, 3605, 0, Some(2)), "1h 5.00s");
+        assert_eq!(run(Compact, 59, 999_600_000, Some(3)), "1m");
+        assert_eq!(run(Compact, 1, 0, Some(11)), "1.00000000000s");
+    }
+
+    #[test]
+    fn clock() {
+        use DurationStyle::Clock;
+        assert_eq!(run(Clock, 0, 0, None), "00:00");
+        assert_eq!(run(Clock, 63, 200_000_000, Some(3)), "01:03.200");
+        assert_eq!(run(Clock, 3723, 0, None), "1:02:03");
+        assert_eq!(run(Clock, 90000, 0, None), "25:00:00");
+        assert_eq!(run(Clock, 59, 999_600_000, Some(0)), "01:00");
+    }
+
+    #[test]
+    fn iso8601() {
+        use DurationStyle::Iso8601;
+        assert_eq!(run(Iso8601, 0, 0, None), "PT0S");
+        assert_eq!(run(Iso8601, 86400, 0, None), "P1D");
+        assert_eq!(run(Iso8601, 93600, 0, None), "P1DT2H");
+        assert_eq!(run(Iso8601, 3600, 0, None), "PT1H");
+        assert_eq!(run(Iso8601, 63, 250_000_000, Some(1)), "PT1M3.3S");
+    }
+
+    #[test]
+    fn padding() {
+        let duration = DurationFmt(Duration::from_secs(63), DurationStyle::Compact);
+        assert_eq!(format!("{duration:<8}|"), "1m 3s   |");
+        assert_eq!(format!("{duration:*^9}"), "**1m 3s**");
+        assert_eq!(format!("{duration:3}"), "1m 3s");
+    }
+}
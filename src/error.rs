@@ -0,0 +1,80 @@
+use core::fmt::{self, Formatter};
+
+/// Invalid [`FormatterBuilder`](crate::FormatterBuilder) specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SpecError {
+    /// Fill is set, but align is unset
+    FillWithoutAlignment,
+    /// Width is greater than [`u16::MAX`]
+    WidthOverflow(usize),
+    /// Precision is greater than [`u16::MAX`]
+    PrecisionOverflow(usize),
+    /// Fill character is not supported by [`Fill`](crate::Fill)
+    UnsupportedFill(char),
+    /// Two flags are set, but the second overrides the first
+    ConflictingFlags(&'static str, &'static str),
+}
+
+impl SpecError {
+    /// Is [`SpecError::FillWithoutAlignment`]
+    pub fn is_fill_without_alignment(&self) -> bool {
+        matches!(self, Self::FillWithoutAlignment)
+    }
+
+    /// Get the overflowed width of [`SpecError::WidthOverflow`]
+    pub fn width_overflow(&self) -> Option<usize> {
+        match *self {
+            Self::WidthOverflow(width) => Some(width),
+            _ => None,
+        }
+    }
+
+    /// Get the overflowed precision of [`SpecError::PrecisionOverflow`]
+    pub fn precision_overflow(&self) -> Option<usize> {
+        match *self {
+            Self::PrecisionOverflow(precision) => Some(precision),
+            _ => None,
+        }
+    }
+
+    /// Get the rejected char of [`SpecError::UnsupportedFill`]
+    pub fn unsupported_fill(&self) -> Option<char> {
+        match *self {
+            Self::UnsupportedFill(ch) => Some(ch),
+            _ => None,
+        }
+    }
+
+    /// Get the flag names of [`SpecError::ConflictingFlags`]
+    pub fn conflicting_flags(&self) -> Option<(&'static str, &'static str)> {
+        match *self {
+            Self::ConflictingFlags(a, b) => Some((a, b)),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SpecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::FillWithoutAlignment => write!(f, "fill is set without align"),
+            Self::WidthOverflow(width) => {
+                write!(f, "width {width} is greater than {}", u16::MAX)
+            },
+            Self::PrecisionOverflow(precision) => {
+                write!(f, "precision {precision} is greater than {}", u16::MAX)
+            },
+            Self::UnsupportedFill(ch) => write!(f, "unsupported fill char {ch:?}"),
+            Self::ConflictingFlags(a, b) => write!(f, "flag {a} is overridden by {b}"),
+        }
+    }
+}
+
+impl core::error::Error for SpecError {}
+
+impl From<crate::TryFromCharError> for SpecError {
+    fn from(value: crate::TryFromCharError) -> Self {
+        Self::UnsupportedFill(value.as_char())
+    }
+}
@@ -0,0 +1,480 @@
+//! Float notation, rewrite core float output into other notations
+//!
+//! Core [`Display`] and [`LowerExp`] do the rounding,
+//! [`FloatWriter`] only moves the point, trims zeros and restyles the exponent
+
+use core::fmt::{self, Display, LowerExp, Write};
+
+use crate::{
+    post::{self, Sink},
+    round::write_rounded,
+    FormatterBuilder, RoundingMode,
+};
+
+/// Notation of floats, used by [`FormatterBuilder::float`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FloatStyle {
+    /// Like `{}`, precision is the fraction digits, e.g `1234.5`
+    #[default]
+    Fixed,
+    /// Like `{:e}`, precision is the fraction digits of mantissa, e.g `1.2345e3`
+    Scientific,
+    /// Like printf `%g`, precision is the significant digits,
+    /// scientific when the exponent is less than -4 or not less than precision,
+    /// trailing zeros are trimmed unless [`alternate`](FormatterBuilder::alternate)
+    ///
+    /// Without precision, it's the shortest digits,
+    /// and scientific when the exponent is less than -4 or not less than 16
+    General,
+    /// Exponent is a multiple of 3, precision is the fraction digits of mantissa, e.g `1.2345e3` `12.5e-6`
+    Engineering,
+}
+
+/// Float notation settings, used by [`float`](FormatterBuilder::float)
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// let mut writter = String::new();
+/// FormatterBuilder::new()
+///     .float_style(FloatStyle::Scientific)
+///     .exponent_digits(2)
+///     .exponent_sign(true)
+///     .precision(2)
+///     .float(&mut writter, 1234.5)
+///     .unwrap();
+/// assert_eq!(writter, "1.23e+03");
+/// ```
+impl FormatterBuilder<'_> {
+    /// Notation of [`float`](FormatterBuilder::float), not used by [`with`](FormatterBuilder::with)
+    pub fn float_style(&mut self, float_style: FloatStyle) -> &mut Self {
+        self.float_style = float_style;
+        self
+    }
+
+    /// Minimum digits of exponent in [`float`](FormatterBuilder::float), padded with zeros, like `1e05`
+    pub fn exponent_digits(&mut self, exponent_digits: u8) -> &mut Self {
+        self.exponent_digits = exponent_digits;
+        self
+    }
+
+    /// Format with exactly the significant digits in [`float`](FormatterBuilder::float),
+    /// instead of [`precision`](FormatterBuilder::precision).
+    /// [`with`](FormatterBuilder::with) still uses the precision
+    ///
+    /// [`FloatStyle::General`] is scientific when the exponent is less than -4 or not less than the digits,
+    /// else fixed, and trailing zeros are kept
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut b = FormatterBuilder::new();
+    /// b.significant_digits(3);
+    ///
+    /// let mut writter = String::new();
+    /// b.float(&mut writter, 0.000123456).unwrap();
+    /// assert_eq!(writter, "0.000123");
+    ///
+    /// let mut writter = String::new();
+    /// b.sign(Sign::Plus).width(10).float(&mut writter, 123456.0).unwrap();
+    /// assert_eq!(writter, "   +123000");
+    ///
+    /// let mut writter = String::new();
+    /// b.float_style(FloatStyle::General).float(&mut writter, 1234567.0).unwrap();
+    /// assert_eq!(writter, "   +1.23e6");
+    /// ```
+    pub fn significant_digits(&mut self, significant_digits: impl Into<Option<u16>>) -> &mut Self {
+        self.significant_digits = significant_digits.into();
+        self
+    }
+
+    /// Always write the sign of exponent in [`float`](FormatterBuilder::float), like `1e+5`
+    pub fn exponent_sign(&mut self, exponent_sign: bool) -> &mut Self {
+        self.exponent_sign = exponent_sign;
+        self
+    }
+
+    /// Format a float in [`float_style`](FormatterBuilder::float_style)
+    ///
+    /// Sign, precision, width, fill, align and post-processing are applied like [`with`](FormatterBuilder::with)
+    ///
+    /// Padding before an output longer than 1024 bytes is not written, the output is streamed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut b = FormatterBuilder::new();
+    /// b.float_style(FloatStyle::General).precision(3).width(9);
+    ///
+    /// let mut writter = String::new();
+    /// b.float(&mut writter, 1234.5).unwrap();
+    /// assert_eq!(writter, "   1.23e3");
+    ///
+    /// let mut writter = String::new();
+    /// b.float_style(FloatStyle::Engineering).precision(1).float(&mut writter, 0.0000125f32).unwrap();
+    /// assert_eq!(writter, "  12.5e-6");
+    /// ```
+    pub fn float<W, T>(&self, writer: W, value: T) -> fmt::Result
+    where
+        W: Write,
+        T: Display + LowerExp,
+    {
+        // sign is written by the inner builder, else exponent sign is replaced by Sign::Space
+        let outer = FormatterBuilder {
+            sign: None,
+            int_precision: false,
+            ..self.clone()
+        };
+        post::with_numeric(&outer, writer, |f| match self.special_values {
+            Some(special_values) => special_values.write(f, |w| self.write_float(w, &value)),
+            None => self.write_float(f, &value),
+        })
+    }
+
+    fn write_float<T: Display + LowerExp>(&self, w: &mut dyn Write, x: &T) -> fmt::Result {
+        let mut exp_style = ExpStyle {
+            min_digits: self.exponent_digits,
+            always_sign: self.exponent_sign,
+            ..ExpStyle::default()
+        };
+        let mut inner = FormatterBuilder::new();
+        inner.sign(self.sign).precision(self.precision);
+
+        let (exp, shift, trim) = match (self.float_style, self.precision, self.significant_digits) {
+            (_, _, Some(digits)) => {
+                let digits = digits.max(1);
+                let exp = exponent(x, self.rounding, Some(digits - 1))?;
+                let fixed = match self.float_style {
+                    FloatStyle::Fixed => true,
+                    FloatStyle::General => (-4..i32::from(digits)).contains(&exp),
+                    FloatStyle::Scientific | FloatStyle::Engineering => false,
+                };
+
+                if fixed && exp < 0 {
+                    inner.precision((i32::from(digits) - 1 - exp) as u16);
+                    (false, 0, false)
+                } else {
+                    // the digits rounded in scientific, then the point moved
+                    inner.precision(digits - 1);
+                    exp_style.hidden = fixed;
+                    match self.float_style {
+                        FloatStyle::Engineering => (true, exp.rem_euclid(3) as usize, false),
+                        _ if fixed => (true, exp as usize, false),
+                        _ => (true, 0, false),
+                    }
+                }
+            },
+            (FloatStyle::Fixed, _, None) => (false, 0, false),
+            (FloatStyle::Scientific, _, None) => (true, 0, false),
+            (FloatStyle::General, None, None) => {
+                let exp = exponent(x, self.rounding, None)?;
+                (!(-4..16).contains(&exp), 0, false)
+            },
+            (FloatStyle::General, Some(precision), None) => {
+                let p = precision.max(1);
+                let exp = exponent(x, self.rounding, Some(p - 1))?;
+                if (-4..i32::from(p)).contains(&exp) {
+                    inner.precision((i32::from(p) - 1 - exp) as u16);
+                    (false, 0, !self.alternate)
+                } else {
+                    inner.precision(p - 1);
+                    (true, 0, !self.alternate)
+                }
+            },
+            (FloatStyle::Engineering, precision, None) => {
+                let mut exp = exponent(x, self.rounding, None)?;
+                if let Some(precision) = precision {
+                    let shift = exp.rem_euclid(3) as u16;
+                    exp = exponent(x, self.rounding, Some(precision.saturating_add(shift)))?;
+                    inner.precision(precision.saturating_add(exp.rem_euclid(3) as u16));
+                }
+                (true, exp.rem_euclid(3) as usize, false)
+            },
+        };
+
+        let force_point = self.alternate && self.float_style == FloatStyle::General;
+        let mut fw = FloatWriter::new(w, trim, force_point, false)
+            .exp_style(exp_style)
+            .shift(shift);
+        write_rounded(&mut fw, x, &inner, exp, self.rounding)?;
+        fw.finish()
+    }
+}
+
+/// Decimal exponent of the float, after rounded to precision
+pub(crate) fn exponent<T>(x: &T, rounding: RoundingMode, precision: Option<u16>) -> Result<i32, fmt::Error>
+where
+    T: Display + LowerExp + ?Sized,
+{
+    let mut sink = Sink;
+    let mut fw = FloatWriter::new(&mut sink, false, false, false);
+    let mut b = FormatterBuilder::new();
+    write_rounded(&mut fw, x, b.precision(precision), true, rounding)?;
+    Ok(fw.exp_value())
+}
+
+/// Style of exponent written by [`FloatWriter`]
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct ExpStyle {
+    /// Minimum digits, padded with zeros
+    pub min_digits: u8,
+    /// Write `+` for non-negative exponent
+    pub always_sign: bool,
+    pub upper: bool,
+    /// No exponent, the point is moved to the position of exponent zero
+    pub hidden: bool,
+}
+
+/// Rewrite core float output
+///
+/// - Trim trailing zeros of fraction
+/// - Force decimal point, or `.0` in fixed notation
+/// - Move the point right, and the exponent is decreased, e.g `1.25e4` into `12.5e3`
+/// - Exponent `e3` into `e+03` etc
+pub(crate) struct FloatWriter<'a> {
+    inner: &'a mut dyn Write,
+    trim: bool,
+    force_point: bool,
+    dot_zero: bool,
+    exp_style: ExpStyle,
+    shift: usize,
+    /// Digits to move before the point
+    shift_left: usize,
+    in_fraction: bool,
+    point_written: bool,
+    pending_point: bool,
+    pending_zeros: usize,
+    in_exp: bool,
+    exp_negative: bool,
+    exp: i32,
+}
+
+impl<'a> FloatWriter<'a> {
+    pub(crate) fn new(inner: &'a mut dyn Write, trim: bool, force_point: bool, dot_zero: bool) -> Self {
+        Self {
+            inner,
+            trim,
+            force_point,
+            dot_zero,
+            exp_style: ExpStyle::default(),
+            shift: 0,
+            shift_left: 0,
+            in_fraction: false,
+            point_written: false,
+            pending_point: false,
+            pending_zeros: 0,
+            in_exp: false,
+            exp_negative: false,
+            exp: 0,
+        }
+    }
+
+    pub(crate) fn exp_style(mut self, exp_style: ExpStyle) -> Self {
+        self.exp_style = exp_style;
+        self
+    }
+
+    /// Move the point right in scientific notation
+    pub(crate) fn shift(mut self, shift: usize) -> Self {
+        self.shift = shift;
+        self.shift_left = shift;
+        self
+    }
+
+    /// Exponent in the core output
+    pub(crate) fn exp_value(&self) -> i32 {
+        if self.exp_negative { -self.exp } else { self.exp }
+    }
+
+    /// End of mantissa, drop trimmed zeros and write forced point
+    fn end_mantissa(&mut self, dot_zero: bool) -> fmt::Result {
+        for _ in 0..self.shift_left {
+            self.inner.write_char('0')?;
+        }
+        self.shift_left = 0;
+        self.pending_point = false;
+        self.pending_zeros = 0;
+        if !self.point_written && (self.force_point || dot_zero) {
+            self.inner.write_char('.')?;
+            if dot_zero {
+                self.inner.write_char('0')?;
+            }
+            self.point_written = true;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(&mut self) -> fmt::Result {
+        if self.in_exp && self.exp_style.hidden {
+            Ok(())
+        } else if self.in_exp {
+            let exp = self.exp_value() - self.shift as i32;
+            let style = self.exp_style;
+            let e = if style.upper { 'E' } else { 'e' };
+            let sign = match exp < 0 {
+                true => "-",
+                false if style.always_sign => "+",
+                false => "",
+            };
+            let digits = usize::from(style.min_digits);
+            write!(self.inner, "{e}{sign}{:0digits$}", exp.unsigned_abs())
+        } else {
+            self.end_mantissa(self.dot_zero)
+        }
+    }
+}
+
+impl Write for FloatWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().try_for_each(|ch| self.write_char(ch))
+    }
+
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        if self.in_exp {
+            match ch {
+                '-' => self.exp_negative = true,
+                '0'..='9' => self.exp = self.exp * 10 + (ch as i32 - '0' as i32),
+                _ => (),
+            }
+            return Ok(());
+        }
+
+        match ch {
+            'e' | 'E' => {
+                self.in_exp = true;
+                self.end_mantissa(false)
+            },
+            '.' if self.shift_left != 0 => {
+                self.in_fraction = true;
+                Ok(())
+            },
+            '0'..='9' if self.in_fraction && self.shift_left != 0 => {
+                self.shift_left -= 1;
+                // point is written before the next digit
+                self.pending_point = self.shift_left == 0;
+                self.inner.write_char(ch)
+            },
+            '.' if self.trim => {
+                self.in_fraction = true;
+                self.pending_point = true;
+                Ok(())
+            },
+            '.' => {
+                self.in_fraction = true;
+                self.point_written = true;
+                self.inner.write_char(ch)
+            },
+            '0' if self.trim && self.in_fraction => {
+                self.pending_zeros += 1;
+                Ok(())
+            },
+            _ => {
+                if self.pending_point {
+                    self.pending_point = false;
+                    self.point_written = true;
+                    self.inner.write_char('.')?;
+                }
+                for _ in 0..self.pending_zeros {
+                    self.inner.write_char('0')?;
+                }
+                self.pending_zeros = 0;
+                self.inner.write_char(ch)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Alignment, Fill, Sign};
+
+    fn run(b: &FormatterBuilder<'_>, x: f64) -> String {
+        let mut out = String::new();
+        b.float(&mut out, x).unwrap();
+        out
+    }
+
+    #[test]
+    fn styles() {
+        let cases = [
+            (1234.5, "1234.5", "1.2345e3", "1234.5", "1.2345e3"),
+            (0.0000125, "0.0000125", "1.25e-5", "1.25e-5", "12.5e-6"),
+            (1e4, "10000", "1e4", "10000", "10e3"),
+            (1e16, "10000000000000000", "1e16", "1e16", "10e15"),
+            (0.0, "0", "0e0", "0", "0e0"),
+            (-0.5, "-0.5", "-5e-1", "-0.5", "-500e-3"),
+            (f64::NAN, "NaN", "NaN", "NaN", "NaN"),
+        ];
+        let mut b = FormatterBuilder::new();
+        for (x, fixed, sci, general, eng) in cases {
+            assert_eq!(run(b.float_style(FloatStyle::Fixed), x), fixed);
+            assert_eq!(run(b.float_style(FloatStyle::Scientific), x), sci);
+            assert_eq!(run(b.float_style(FloatStyle::General), x), general);
+            assert_eq!(run(b.float_style(FloatStyle::Engineering), x), eng);
+        }
+    }
+
+    #[test]
+    fn precision() {
+        let mut b = FormatterBuilder::new();
+        b.float_style(FloatStyle::General).precision(3);
+        assert_eq!(run(&b, 1234.5), "1.23e3");
+        assert_eq!(run(&b, 123.0), "123");
+        assert_eq!(run(&b, 0.0001), "0.0001");
+        assert_eq!(run(&b, 0.00001), "1e-5");
+        assert_eq!(run(&b, 999.6), "1e3");
+        assert_eq!(run(b.alternate(true), 123.0), "123.");
+        assert_eq!(run(&b, 1.0), "1.00");
+
+        b.alternate(false).float_style(FloatStyle::Engineering).precision(2);
+        assert_eq!(run(&b, 1234.5), "1.23e3");
+        assert_eq!(run(&b, 12346.0), "12.35e3");
+        assert_eq!(run(&b, 999.996), "1.00e3");
+        assert_eq!(run(&b, 0.0), "0.00e0");
+        assert_eq!(run(b.precision(0), 12345.0), "12e3");
+
+        b.float_style(FloatStyle::Scientific).precision(2);
+        assert_eq!(run(&b, 1234.5), "1.23e3");
+        assert_eq!(run(b.float_style(FloatStyle::Fixed), 1234.5), "1234.50");
+    }
+
+    #[test]
+    fn significant_digits() {
+        let cases = [
+            (0.000123456, "0.000123", "1.23e-4", "0.000123", "123e-6"),
+            (9.996, "10.0", "1.00e1", "10.0", "10.0e0"),
+            (123456.0, "123000", "1.23e5", "1.23e5", "123e3"),
+            (0.0, "0.00", "0.00e0", "0.00", "0.00e0"),
+            (-1.0, "-1.00", "-1.00e0", "-1.00", "-1.00e0"),
+            (f64::INFINITY, "inf", "inf", "inf", "inf"),
+        ];
+        let mut b = FormatterBuilder::new();
+        b.significant_digits(3).precision(1);
+        for (x, fixed, sci, general, eng) in cases {
+            assert_eq!(run(b.float_style(FloatStyle::Fixed), x), fixed);
+            assert_eq!(run(b.float_style(FloatStyle::Scientific), x), sci);
+            assert_eq!(run(b.float_style(FloatStyle::General), x), general);
+            assert_eq!(run(b.float_style(FloatStyle::Engineering), x), eng);
+        }
+
+        b.significant_digits(1).float_style(FloatStyle::Engineering);
+        assert_eq!(run(&b, 0.0000125), "10e-6");
+        assert_eq!(run(b.float_style(FloatStyle::Fixed).fill_align(Fill::Zero, Alignment::Left).width(5), 1.5), "20000");
+        assert_eq!(run(b.fill_align(None, Alignment::Center).width(7), -0.05), " -0.05 ");
+    }
+
+    #[test]
+    fn exponent_style() {
+        let mut b = FormatterBuilder::new();
+        b.float_style(FloatStyle::Scientific).exponent_digits(3).exponent_sign(true);
+        assert_eq!(run(&b, 1234.5), "1.2345e+003");
+        assert_eq!(run(&b, 0.00125), "1.25e-003");
+        assert_eq!(run(b.sign(Sign::Space), 1e300), " 1e+300");
+        assert_eq!(run(b.sign(Sign::Plus).width(12), -1e-300), "     -1e-300");
+        assert_eq!(run(b.sign(None).sign_aware_zero_pad(true), 1.0), "0000001e+000");
+    }
+}
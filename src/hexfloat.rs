@@ -0,0 +1,401 @@
+//! Hexadecimal float adapter, like C `%a`, e.g `0x1.91eb851eb851fp+1`
+
+use core::{
+    fmt::{self, Display, Formatter, LowerHex, UpperHex, Write},
+    str::FromStr,
+};
+
+use crate::{
+    number::{Pad, PadAlign},
+    post::{Count, Sink},
+};
+
+const FRACTION_BITS: u32 = 52;
+const FRACTION_MASK: u64 = (1 << FRACTION_BITS) - 1;
+
+/// Format float in hexadecimal, like C `%a`
+///
+/// - Precision is the count of hex digits after the point, rounded half to even,
+///   default is the exact digits without trailing zeros
+/// - [`LowerHex`] and [`Display`] are lower case, [`UpperHex`] is upper case
+/// - Alternate writes the `0x` prefix
+/// - Subnormals are normalized, like `1p-1074`
+///
+/// Parse it by [`FromStr`], the prefix is optional
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// assert_eq!(format!("{:#x}", HexFloat(3.14)), "0x1.91eb851eb851fp+1");
+/// assert_eq!(format!("{:+X}", HexFloat(-0.5)), "-1P-1");
+/// assert_eq!(format!("{:#012.2x}", HexFloat(1.0)), "0x0001.00p+0");
+/// assert_eq!(format!("{:>8}", HexFloat::from(1.5f32)), "  1.8p+0");
+///
+/// let x: HexFloat = "0x1.91eb851eb851fp+1".parse().unwrap();
+/// assert_eq!(x, HexFloat(3.14));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HexFloat(pub f64);
+
+impl From<f32> for HexFloat {
+    fn from(value: f32) -> Self {
+        Self(value.into())
+    }
+}
+
+impl From<f64> for HexFloat {
+    fn from(value: f64) -> Self {
+        Self(value)
+    }
+}
+
+impl HexFloat {
+    /// Leading digit, fraction bits and binary exponent, after rounded to `digits` hex digits
+    fn parts(&self, digits: Option<usize>) -> (u64, u64, i32) {
+        let bits = self.0.to_bits();
+        let field = ((bits >> FRACTION_BITS) & 0x7ff) as i32;
+        let mut fraction = bits & FRACTION_MASK;
+
+        let mut exp = match field {
+            0 if fraction == 0 => return (0, 0, 0),
+            0 => {
+                // subnormal, normalize it
+                let shift = fraction.leading_zeros() - (63 - FRACTION_BITS);
+                fraction = (fraction << shift) & FRACTION_MASK;
+                -1022 - shift as i32
+            },
+            _ => field - 1023,
+        };
+
+        if let Some(digits) = digits.filter(|&digits| digits < 13) {
+            let drop = FRACTION_BITS - digits as u32 * 4;
+            let kept = fraction >> drop;
+            let dropped = fraction & ((1 << drop) - 1);
+            let half = 1 << (drop - 1);
+            // the leading digit is odd when no digits kept
+            let odd = digits == 0 || kept & 1 == 1;
+            let round_up = dropped > half || dropped == half && odd;
+            fraction = (kept + u64::from(round_up)) << drop;
+
+            if fraction > FRACTION_MASK {
+                fraction = 0;
+                exp += 1;
+            }
+        }
+        (1, fraction, exp)
+    }
+
+    fn write_body(&self, w: &mut dyn Write, upper: bool, precision: Option<usize>) -> fmt::Result {
+        let x = self.0;
+        if x.is_nan() {
+            return w.write_str(if upper { "NAN" } else { "NaN" });
+        }
+        if x.is_infinite() {
+            return w.write_str(if upper { "INF" } else { "inf" });
+        }
+
+        let (lead, fraction, exp) = self.parts(precision);
+        let digits = precision.unwrap_or_else(|| {
+            if fraction == 0 { 0 } else { 13 - fraction.trailing_zeros() as usize / 4 }
+        });
+
+        write!(w, "{lead}")?;
+        if digits != 0 {
+            w.write_char('.')?;
+        }
+        for i in 0..digits {
+            let digit = if i < 13 { (fraction >> (48 - i * 4)) & 0xf } else { 0 };
+            if upper {
+                write!(w, "{digit:X}")?;
+            } else {
+                write!(w, "{digit:x}")?;
+            }
+        }
+        // exponent sign is not alone, else replaced by Sign::Space of builder
+        let p = match (upper, exp < 0) {
+            (false, false) => "p+",
+            (false, true) => "p-",
+            (true, false) => "P+",
+            (true, true) => "P-",
+        };
+        w.write_str(p)?;
+        write!(w, "{}", exp.unsigned_abs())
+    }
+
+    fn write(&self, f: &mut Formatter<'_>, upper: bool) -> fmt::Result {
+        let precision = f.precision();
+        let sign = match (self.0.is_sign_negative() && !self.0.is_nan(), f.sign_plus()) {
+            (true, _) => "-",
+            (false, true) => "+",
+            (false, false) => "",
+        };
+        let prefix = match (f.alternate() && self.0.is_finite(), upper) {
+            (false, _) => "",
+            (true, false) => "0x",
+            (true, true) => "0X",
+        };
+
+        let mut count = Count { inner: Sink, chars: 0 };
+        self.write_body(&mut count, upper, precision)?;
+        let pad = Pad::from_formatter(f, PadAlign::Right);
+        let (pre, post) = pad.split(sign.len() + prefix.len() + count.chars);
+
+        if pad.align != PadAlign::AfterSign {
+            pad.write_fill(f, pre)?;
+        }
+        // write sign alone, to be replaced by Sign::Space of builder
+        f.write_str(sign)?;
+        f.write_str(prefix)?;
+        if pad.align == PadAlign::AfterSign {
+            pad.write_fill(f, pre)?;
+        }
+        self.write_body(f, upper, precision)?;
+        pad.write_fill(f, post)
+    }
+}
+
+impl Display for HexFloat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+impl LowerHex for HexFloat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write(f, false)
+    }
+}
+
+impl UpperHex for HexFloat {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.write(f, true)
+    }
+}
+
+/// Error of parsing [`HexFloat`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseHexFloatError {
+    /// No digits in mantissa or exponent
+    MissingDigits,
+    /// Unexpected char
+    InvalidChar(char),
+}
+
+impl fmt::Display for ParseHexFloatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::MissingDigits => write!(f, "missing digits in hex float"),
+            Self::InvalidChar(ch) => write!(f, "invalid char {ch:?} in hex float"),
+        }
+    }
+}
+
+impl core::error::Error for ParseHexFloatError {}
+
+impl FromStr for HexFloat {
+    type Err = ParseHexFloatError;
+
+    /// Parse like C `strtod`, e.g `-0x1.8p+1` `1.8P1` `0x10` `inf` `nan`, rounded half to even
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (negative, s) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let signed = |x: f64| Ok(Self(if negative { -x } else { x }));
+
+        if s.eq_ignore_ascii_case("nan") {
+            return signed(f64::NAN);
+        }
+        if s.eq_ignore_ascii_case("inf") || s.eq_ignore_ascii_case("infinity") {
+            return signed(f64::INFINITY);
+        }
+        let s = s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")).unwrap_or(s);
+
+        let mut mantissa = 0u64;
+        let mut sticky = false;
+        let mut exp = 0i64;
+        let mut any_digit = false;
+        let mut in_fraction = false;
+        let mut chars = s.chars();
+
+        for ch in chars.by_ref() {
+            match ch {
+                '.' if !in_fraction => in_fraction = true,
+                'p' | 'P' => {
+                    exp += parse_exp(chars.as_str())?;
+                    break;
+                },
+                _ => {
+                    let digit = ch.to_digit(16).ok_or(ParseHexFloatError::InvalidChar(ch))?;
+                    any_digit = true;
+                    if mantissa >> 60 == 0 {
+                        mantissa = mantissa << 4 | u64::from(digit);
+                        exp -= 4 * i64::from(in_fraction);
+                    } else {
+                        sticky |= digit != 0;
+                        exp += 4 * i64::from(!in_fraction);
+                    }
+                },
+            }
+        }
+        if !any_digit {
+            return Err(ParseHexFloatError::MissingDigits);
+        }
+
+        signed(compose(mantissa, sticky, exp))
+    }
+}
+
+/// Decimal exponent with optional sign, saturating
+fn parse_exp(s: &str) -> Result<i64, ParseHexFloatError> {
+    let (negative, digits) = match s.as_bytes().first() {
+        Some(b'-') => (true, &s[1..]),
+        Some(b'+') => (false, &s[1..]),
+        _ => (false, s),
+    };
+    if digits.is_empty() {
+        return Err(ParseHexFloatError::MissingDigits);
+    }
+
+    let mut exp = 0i64;
+    for ch in digits.chars() {
+        let digit = ch.to_digit(10).ok_or(ParseHexFloatError::InvalidChar(ch))?;
+        exp = (exp * 10 + i64::from(digit)).min(1 << 20);
+    }
+    Ok(if negative { -exp } else { exp })
+}
+
+/// `mantissa * 2^exp` into float, `sticky` is any nonzero bits dropped from mantissa
+fn compose(mantissa: u64, sticky: bool, exp: i64) -> f64 {
+    if mantissa == 0 {
+        return 0.0;
+    }
+    let shift = mantissa.leading_zeros();
+    let mantissa = mantissa << shift;
+    // exponent of the leading bit
+    let top = exp - i64::from(shift) + 63;
+    if top > 1023 {
+        return f64::INFINITY;
+    }
+
+    // bits to keep, less for subnormals
+    let keep = if top >= -1022 { 53 } else { 53 - (-1022 - top) };
+    let drop = 64 - keep;
+    let (kept, half, rest) = match drop {
+        65.. => (0, false, true),
+        64 => (0, true, mantissa << 1 != 0 || sticky),
+        _ => (
+            mantissa >> drop,
+            mantissa >> (drop - 1) & 1 == 1,
+            mantissa & ((1 << (drop - 1)) - 1) != 0 || sticky,
+        ),
+    };
+    let rounded = kept + u64::from(half && (rest || kept & 1 == 1));
+
+    if top < -1022 {
+        // subnormal, carry into the exponent field is the minimum normal
+        return f64::from_bits(rounded);
+    }
+    let (rounded, top) = if rounded >> 53 != 0 { (rounded >> 1, top + 1) } else { (rounded, top) };
+    if top > 1023 {
+        return f64::INFINITY;
+    }
+    f64::from_bits(((top + 1023) as u64) << FRACTION_BITS | (rounded & FRACTION_MASK))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FormatterBuilder, Sign};
+
+    #[test]
+    fn format() {
+        let cases = [
+            (1.0, "0x1p+0"),
+            (-2.0, "-0x1p+1"),
+            (0.0, "0x0p+0"),
+            (-0.0, "-0x0p+0"),
+            (0.1, "0x1.999999999999ap-4"),
+            (f64::MAX, "0x1.fffffffffffffp+1023"),
+            (f64::MIN_POSITIVE, "0x1p-1022"),
+            (5e-324, "0x1p-1074"),
+            (f64::from_bits(3), "0x1.8p-1073"),
+            (f64::NAN, "NaN"),
+            (f64::NEG_INFINITY, "-inf"),
+        ];
+        for (x, exp) in cases {
+            assert_eq!(format!("{:#x}", HexFloat(x)), exp);
+        }
+        assert_eq!(format!("{:#X}", HexFloat(0.1)), "0X1.999999999999AP-4");
+        assert_eq!(format!("{:X}", HexFloat(f64::INFINITY)), "INF");
+    }
+
+    #[test]
+    fn precision() {
+        assert_eq!(format!("{:.0}", HexFloat(1.5)), "1p+1");
+        assert_eq!(format!("{:.0}", HexFloat(1.0 + 0.5 - f64::EPSILON)), "1p+0");
+        assert_eq!(format!("{:.1}", HexFloat(0.1)), "1.ap-4");
+        assert_eq!(format!("{:.2}", HexFloat(1.99999)), "1.00p+1");
+        assert_eq!(format!("{:.3}", HexFloat(1.0)), "1.000p+0");
+        assert_eq!(format!("{:.15}", HexFloat(0.1)), "1.999999999999a00p-4");
+    }
+
+    #[test]
+    fn padding() {
+        assert_eq!(format!("{:+#10x}", HexFloat(1.0)), "   +0x1p+0");
+        assert_eq!(format!("{:<+#10x}", HexFloat(1.0)), "+0x1p+0   ");
+        assert_eq!(format!("{:+#010x}", HexFloat(1.0)), "+0x0001p+0");
+        assert_eq!(format!("{:*^9}", HexFloat(-1.0)), "**-1p+0**");
+
+        let mut out = String::new();
+        FormatterBuilder::new().sign(Sign::Space).alternate(true).with(&mut out, |f| {
+            fmt::LowerHex::fmt(&HexFloat(2.5), f)
+        }).unwrap();
+        assert_eq!(out, " 0x1.4p+1");
+    }
+
+    #[test]
+    fn parse() {
+        let cases = [
+            ("0x1p+0", 1.0),
+            ("-0X1.8P1", -3.0),
+            ("1.8p1", 3.0),
+            ("0x10", 16.0),
+            ("0x.8", 0.5),
+            ("0x1p-1074", 5e-324),
+            ("0x1p-1075", 0.0),
+            ("0x1.0000000000001p-1075", 5e-324),
+            ("0x1.fffffffffffff8p+1023", f64::INFINITY),
+            ("0x1.fffffffffffff7ffp+1023", f64::MAX),
+            ("0x1.00000000000008p+0", 1.0),
+            ("0x1.000000000000080000001p+0", 1.0 + f64::EPSILON),
+            ("inf", f64::INFINITY),
+            ("-Infinity", f64::NEG_INFINITY),
+        ];
+        for (s, exp) in cases {
+            assert_eq!(s.parse::<HexFloat>().unwrap().0, exp, "{s}");
+        }
+        assert!("nan".parse::<HexFloat>().unwrap().0.is_nan());
+        assert_eq!("0x".parse::<HexFloat>(), Err(ParseHexFloatError::MissingDigits));
+        assert_eq!("1p".parse::<HexFloat>(), Err(ParseHexFloatError::MissingDigits));
+        assert_eq!("1g".parse::<HexFloat>(), Err(ParseHexFloatError::InvalidChar('g')));
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut x = 1.2345e-310f64;
+        for _ in 0..2000 {
+            for value in [x, -x, f64::from_bits(x.to_bits() ^ 0x5555)] {
+                let s = format!("{:#x}", HexFloat(value));
+                assert_eq!(s.parse::<HexFloat>().unwrap().0.to_bits(), value.to_bits(), "{s}");
+            }
+            x *= 1.7;
+            if x.is_infinite() {
+                break;
+            }
+        }
+    }
+}
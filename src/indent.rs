@@ -0,0 +1,204 @@
+//! Line indenting of the whole output
+
+use core::fmt::{self, Write};
+
+use crate::FormatterBuilder;
+
+const SPACES: &str = "                ";
+
+/// Write `n` spaces in chunks
+pub(crate) fn write_spaces(w: &mut dyn Write, mut n: usize) -> fmt::Result {
+    while n != 0 {
+        let chunk = n.min(SPACES.len());
+        w.write_str(&SPACES[..chunk])?;
+        n -= chunk;
+    }
+    Ok(())
+}
+
+/// Prefix of lines, used by [`FormatterBuilder::indent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Indent<'a> {
+    Spaces(u16),
+    Str(&'a str),
+}
+
+impl<'a> FormatterBuilder<'a> {
+    /// Prefix every line the closure writes with `n` spaces, after padding
+    ///
+    /// Empty lines are not indented, nested builders indent again
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::{Debug, Write};
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().indent(2).with(&mut writter, |f| {
+    ///     f.write_str("point:\n")?;
+    ///     FormatterBuilder::new().indent(2).alternate(true).with(f, |f| (1, 2).fmt(f))
+    /// }).unwrap();
+    /// assert_eq!(writter, "  point:\n    (\n        1,\n        2,\n    )");
+    /// ```
+    pub fn indent(&mut self, n: impl Into<Option<u16>>) -> &mut Self {
+        self.indent = n.into().map(Indent::Spaces);
+        self
+    }
+
+    /// Prefix every line the closure writes with `prefix`, after padding
+    ///
+    /// Empty lines are prefixed with trailing whitespaces of `prefix` trimmed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().indent_with("> ").with(&mut writter, |f| {
+    ///     f.write_str("quote\n\nend\n")
+    /// }).unwrap();
+    /// assert_eq!(writter, "> quote\n>\n> end\n");
+    /// ```
+    pub fn indent_with(&mut self, prefix: impl Into<Option<&'a str>>) -> &mut Self {
+        self.indent = prefix.into().map(Indent::Str);
+        self
+    }
+
+    /// Indent the first line of [`indent`](FormatterBuilder::indent), default is true
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut writter = String::from("list: ");
+    /// FormatterBuilder::new().indent(6).indent_first_line(false).with(&mut writter, |f| {
+    ///     f.write_str("a\nb")
+    /// }).unwrap();
+    /// assert_eq!(writter, "list: a\n      b");
+    /// ```
+    pub fn indent_first_line(&mut self, indent_first_line: bool) -> &mut Self {
+        self.indent_skip_first = !indent_first_line;
+        self
+    }
+
+    /// Chars of the line prefix
+    pub(crate) fn indent_width(&self) -> usize {
+        match self.indent {
+            Some(Indent::Spaces(n)) => n.into(),
+            Some(Indent::Str(prefix)) => prefix.chars().count(),
+            None => 0,
+        }
+    }
+}
+
+/// Write the prefix before the first char of each line, [`None`] writes through
+pub(crate) struct IndentWriter<'a, W> {
+    inner: W,
+    indent: Option<Indent<'a>>,
+    line_start: bool,
+}
+
+impl<'a, W: Write> IndentWriter<'a, W> {
+    pub(crate) fn new(inner: W, indent: Option<Indent<'a>>, first_line: bool) -> Self {
+        Self { inner, indent, line_start: first_line }
+    }
+
+    fn write_prefix(&mut self, empty_line: bool) -> fmt::Result {
+        let Some(indent) = self.indent else {
+            return Ok(());
+        };
+        match indent {
+            Indent::Spaces(_) if empty_line => Ok(()),
+            Indent::Spaces(n) => write_spaces(&mut self.inner, n.into()),
+            Indent::Str(prefix) if empty_line => self.inner.write_str(prefix.trim_end()),
+            Indent::Str(prefix) => self.inner.write_str(prefix),
+        }
+    }
+}
+
+impl<W: Write> Write for IndentWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.indent.is_none() {
+            return self.inner.write_str(s);
+        }
+        for (i, line) in s.split('\n').enumerate() {
+            if i != 0 {
+                if self.line_start {
+                    self.write_prefix(true)?;
+                }
+                self.inner.write_char('\n')?;
+                self.line_start = true;
+            }
+            if !line.is_empty() {
+                if self.line_start {
+                    self.write_prefix(false)?;
+                    self.line_start = false;
+                }
+                self.inner.write_str(line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Alignment;
+    use std::fmt::Display;
+
+    #[test]
+    fn indent() {
+        let mut out = String::new();
+        FormatterBuilder::new().indent(20).with(&mut out, |f| f.write_str("a\n\n  b\n")).unwrap();
+        assert_eq!(out, format!("{0}a\n\n{0}  b\n", " ".repeat(20)));
+
+        let mut out = String::new();
+        FormatterBuilder::new().indent_with("| ").indent_first_line(false).with(&mut out, |f| {
+            f.write_str("a")?;
+            f.write_str("\nb\n")?;
+            f.write_char('c')
+        }).unwrap();
+        assert_eq!(out, "a\n| b\n| c");
+
+        let mut out = String::new();
+        FormatterBuilder::new().indent(0).with(&mut out, |f| f.write_str("a\nb")).unwrap();
+        assert_eq!(out, "a\nb");
+
+        let prefix = format!("{}: ", 3);
+        let mut out = String::new();
+        FormatterBuilder::new().indent_with(prefix.as_str()).with(&mut out, |f| f.write_str("a\nb")).unwrap();
+        assert_eq!(out, "3: a\n3: b");
+    }
+
+    #[test]
+    fn nested() {
+        let mut out = String::new();
+        FormatterBuilder::new().indent_with("> ").with(&mut out, |f| {
+            f.write_str("outer\n")?;
+            FormatterBuilder::new().indent_with("> ").with(&mut *f, |f| {
+                f.write_str("inner\n\n")?;
+                FormatterBuilder::new().indent(2).with(f, |f| f.write_str("x\ny"))
+            })?;
+            f.write_str("\nend")
+        }).unwrap();
+        assert_eq!(out, "> outer\n> > inner\n> >\n> >   x\n> >   y\n> end");
+    }
+
+    #[test]
+    fn with_options() {
+        let mut out = String::new();
+        FormatterBuilder::new().indent(2).width(5).align(Alignment::Right).with(&mut out, |f| 1.fmt(f)).unwrap();
+        assert_eq!(out, "      1");
+
+        let mut out = String::new();
+        FormatterBuilder::new().indent(2).grouping(',', 3).with(&mut out, |f| {
+            f.write_str("1234\n5678")
+        }).unwrap();
+        assert_eq!(out, "  1,234\n  5678");
+
+        let mut out = String::new();
+        FormatterBuilder::new().indent(1).precision(1).float(&mut out, 0.25).unwrap();
+        assert_eq!(out, " 0.2");
+    }
+}
@@ -0,0 +1,447 @@
+//! Width-aware layout of [`Debug`](core::fmt::Debug) output, like Wadler's pretty printer
+//!
+//! The closure is formatted in `{:?}`, a group from an open bracket to the close bracket
+//! is written in one line if it fits the rest of the line, or broken like `{:#?}`.
+//! The decision needs a lookahead of the rest of the line, buffered in a fixed size queue
+
+use core::fmt::{self, Formatter, Write};
+
+use crate::{indent::write_spaces, FormatterBuilder, IndentStyle};
+
+/// Capacity of the lookahead in chars, groups longer than it are broken
+const LOOKAHEAD: usize = 512;
+
+/// Default line width without [`width`](FormatterBuilder::width)
+const DEFAULT_WIDTH: usize = 80;
+
+impl FormatterBuilder<'_> {
+    /// Layout the `{:?}` output of the closure within [`width`](FormatterBuilder::width) columns,
+    /// default is 80
+    ///
+    /// Groups in brackets are in one line if they fit, else broken like `{:#?}`,
+    /// indented by [`pretty_indent`](FormatterBuilder::pretty_indent) or 4 spaces.
+    /// Width is the line width, not the padding
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::Debug;
+    /// #[derive(Debug)]
+    /// struct Point { x: i32, y: i32 }
+    ///
+    /// let value = (vec![Point { x: 1, y: 2 }, Point { x: 30, y: 40 }], "end");
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().debug_layout(true).width(32).pretty_indent(IndentStyle::Spaces(2)).with(&mut writter, |f| {
+    ///     value.fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "\
+    /// (
+    ///   [
+    ///     Point { x: 1, y: 2 },
+    ///     Point { x: 30, y: 40 },
+    ///   ],
+    ///   \"end\",
+    /// )");
+    /// ```
+    pub fn debug_layout(&mut self, debug_layout: bool) -> &mut Self {
+        self.debug_layout = debug_layout;
+        self
+    }
+}
+
+/// [`FormatterBuilder::with`] in [`debug_layout`](FormatterBuilder::debug_layout)
+pub(crate) fn with<W, F>(b: &FormatterBuilder<'_>, writer: W, f: F) -> fmt::Result
+where
+    W: Write,
+    F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+{
+    let width = b.width.map_or(DEFAULT_WIDTH, usize::from).saturating_sub(b.indent_width());
+    let inner = FormatterBuilder {
+        alternate: false,
+        width: None,
+        fill_align: None,
+        indent: None,
+        debug_layout: false,
+        ..b.clone()
+    };
+
+    let mut layout = Layout::new(b.indent_writer(writer), width, b.pretty_indent);
+    inner.with(&mut layout as &mut dyn Write, f)?;
+    layout.finish()
+}
+
+fn is_open(ch: char) -> bool {
+    matches!(ch, '(' | '[' | '{')
+}
+
+fn is_close(ch: char) -> bool {
+    matches!(ch, ')' | ']' | '}')
+}
+
+/// Quoted literal state, brackets and commas in quotes are text
+#[derive(Debug, Clone, Copy, Default)]
+struct Quote {
+    quote: Option<char>,
+    escape: bool,
+    prev: char,
+}
+
+impl Quote {
+    /// Update by the char, returns the char is out of quotes
+    fn push(&mut self, ch: char) -> bool {
+        let prev = core::mem::replace(&mut self.prev, ch);
+        match self.quote {
+            Some(_) if self.escape => self.escape = false,
+            Some(_) if ch == '\\' => self.escape = true,
+            Some(quote) => {
+                if ch == quote {
+                    self.quote = None;
+                }
+            },
+            // `'` after a letter is text, like `don't`
+            None if ch == '"' || ch == '\'' && !prev.is_alphanumeric() => self.quote = Some(ch),
+            None => return true,
+        }
+        false
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decision {
+    /// Group of the length fits
+    Fits(usize),
+    Breaks,
+    NeedMore,
+}
+
+struct Layout<W> {
+    inner: W,
+    width: usize,
+    style: Option<IndentStyle>,
+    queue: [char; LOOKAHEAD],
+    head: usize,
+    len: usize,
+    /// Count of open broken groups
+    depth: usize,
+    column: usize,
+    quote: Quote,
+    /// Space maybe before a close bracket
+    held_space: bool,
+    /// Space after an open bracket or comma of broken group
+    skip_space: bool,
+    /// New line without indentation yet
+    line_empty: bool,
+}
+
+impl<W: Write> Layout<W> {
+    fn new(inner: W, width: usize, style: Option<IndentStyle>) -> Self {
+        Self {
+            inner,
+            width,
+            style,
+            queue: [' '; LOOKAHEAD],
+            head: 0,
+            len: 0,
+            depth: 0,
+            column: 0,
+            quote: Quote::default(),
+            held_space: false,
+            skip_space: false,
+            line_empty: false,
+        }
+    }
+
+    fn at(&self, i: usize) -> char {
+        self.queue[(self.head + i) % LOOKAHEAD]
+    }
+
+    fn pop(&mut self) -> char {
+        let ch = self.at(0);
+        self.head = (self.head + 1) % LOOKAHEAD;
+        self.len -= 1;
+        ch
+    }
+
+    fn unit_width(&self) -> usize {
+        match self.style {
+            Some(IndentStyle::Spaces(n)) => n.into(),
+            Some(IndentStyle::Tab) | None => 4,
+        }
+    }
+
+    fn current_column(&self) -> usize {
+        if self.line_empty { self.depth * self.unit_width() } else { self.column }
+    }
+
+    fn emit(&mut self, ch: char) -> fmt::Result {
+        if self.line_empty {
+            self.line_empty = false;
+            self.column = self.depth * self.unit_width();
+            for _ in 0..self.depth {
+                match self.style {
+                    Some(IndentStyle::Spaces(n)) => write_spaces(&mut self.inner, n.into())?,
+                    Some(IndentStyle::Tab) => self.inner.write_char('\t')?,
+                    None => write_spaces(&mut self.inner, 4)?,
+                }
+            }
+        }
+        self.column += 1;
+        self.inner.write_char(ch)
+    }
+
+    fn newline(&mut self) -> fmt::Result {
+        self.column = 0;
+        self.line_empty = true;
+        self.inner.write_char('\n')
+    }
+
+    fn flush_space(&mut self) -> fmt::Result {
+        if self.held_space {
+            self.held_space = false;
+            self.emit(' ')?;
+        }
+        Ok(())
+    }
+
+    /// Decide the group at the queue front
+    fn decide(&self, end: bool) -> Decision {
+        let remaining = self.width.saturating_sub(self.current_column());
+        // trailing comma in broken group
+        let extra = usize::from(self.depth != 0);
+        let mut quote = Quote::default();
+        let mut nesting = 0usize;
+        let mut closed = None;
+
+        for i in 0..self.len {
+            let ch = self.at(i);
+            match closed {
+                None => {
+                    if quote.push(ch) {
+                        if is_open(ch) {
+                            nesting += 1;
+                        } else if is_close(ch) {
+                            nesting -= 1;
+                            if nesting == 0 {
+                                closed = Some(i + 1);
+                                if i == 1 || i == 2 && self.at(1) == ' ' {
+                                    // empty group, like `[]` `{ }`
+                                    return Decision::Fits(i + 1);
+                                }
+                            }
+                        }
+                    }
+                },
+                Some(group) => {
+                    if ch == ',' || ch == '\n' || is_open(ch) || is_close(ch) {
+                        return if i + extra <= remaining { Decision::Fits(group) } else { Decision::Breaks };
+                    }
+                },
+            }
+            // after the check of empty groups
+            if i + 1 > remaining && i >= 2 {
+                return Decision::Breaks;
+            }
+        }
+
+        match closed {
+            _ if !end && self.len == LOOKAHEAD => Decision::Breaks,
+            _ if !end => Decision::NeedMore,
+            Some(group) if self.len + extra <= remaining => Decision::Fits(group),
+            None if self.len <= remaining => Decision::Fits(self.len),
+            _ => Decision::Breaks,
+        }
+    }
+
+    /// Layout the queued chars, until a decision needs more chars
+    fn process(&mut self, end: bool) -> fmt::Result {
+        while self.len != 0 {
+            let ch = self.at(0);
+            if self.quote.quote.is_none() && is_open(ch) {
+                self.flush_space()?;
+                self.skip_space = false;
+                match self.decide(end) {
+                    Decision::NeedMore => return Ok(()),
+                    Decision::Fits(len) => {
+                        for _ in 0..len {
+                            let ch = self.pop();
+                            self.emit(ch)?;
+                        }
+                    },
+                    Decision::Breaks => {
+                        self.pop();
+                        self.emit(ch)?;
+                        self.depth += 1;
+                        self.newline()?;
+                        self.skip_space = true;
+                    },
+                }
+                self.quote.prev = ch;
+                continue;
+            }
+            let ch = self.pop();
+            self.direct(ch)?;
+        }
+        Ok(())
+    }
+
+    /// Layout a char out of undecided groups
+    fn direct(&mut self, ch: char) -> fmt::Result {
+        if !self.quote.push(ch) {
+            self.flush_space()?;
+            self.skip_space = false;
+            return self.emit(ch);
+        }
+        match ch {
+            ' ' if self.skip_space => self.skip_space = false,
+            ' ' => {
+                self.flush_space()?;
+                self.held_space = true;
+            },
+            _ if self.depth != 0 && is_close(ch) => {
+                self.held_space = false;
+                self.skip_space = false;
+                if !self.line_empty {
+                    self.inner.write_char(',')?;
+                    self.newline()?;
+                }
+                self.depth -= 1;
+                self.emit(ch)?;
+            },
+            ',' if self.depth != 0 => {
+                self.held_space = false;
+                self.emit(',')?;
+                self.newline()?;
+                self.skip_space = true;
+            },
+            '\n' => {
+                self.held_space = false;
+                self.skip_space = false;
+                self.newline()?;
+            },
+            _ => {
+                self.flush_space()?;
+                self.skip_space = false;
+                self.emit(ch)?;
+            },
+        }
+        Ok(())
+    }
+
+    fn finish(&mut self) -> fmt::Result {
+        self.process(true)?;
+        self.flush_space()
+    }
+}
+
+impl<W: Write> Write for Layout<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for ch in s.chars() {
+            if self.len == LOOKAHEAD {
+                self.process(false)?;
+            }
+            self.queue[(self.head + self.len) % LOOKAHEAD] = ch;
+            self.len += 1;
+            self.process(false)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::BTreeMap, fmt::Debug};
+
+    fn run(width: u16, value: &dyn Debug) -> String {
+        let mut out = String::new();
+        FormatterBuilder::new().debug_layout(true).width(width).with(&mut out, |f| value.fmt(f)).unwrap();
+        out
+    }
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Config {
+        name: &'static str,
+        ports: Vec<u16>,
+        env: BTreeMap<&'static str, (i32, Option<char>)>,
+        unit: (),
+        one: (u8,),
+        empty: Vec<u8>,
+    }
+
+    fn config() -> Config {
+        Config {
+            name: "a, b) {",
+            ports: vec![80, 443],
+            env: BTreeMap::from([("a", (1, None)), ("b", (2, Some('}')))]),
+            unit: (),
+            one: (1,),
+            empty: vec![],
+        }
+    }
+
+    #[test]
+    fn fits() {
+        let flat = format!("{:?}", config());
+        assert_eq!(run(flat.len() as u16, &config()), flat);
+        assert_eq!(run(500, &config()), flat);
+    }
+
+    #[test]
+    fn breaks() {
+        assert_eq!(run(0, &config()), format!("{:#?}", config()));
+        assert_eq!(run(60, &config()), "\
+Config {
+    name: \"a, b) {\",
+    ports: [80, 443],
+    env: {\"a\": (1, None), \"b\": (2, Some('}'))},
+    unit: (),
+    one: (1,),
+    empty: [],
+}");
+        assert_eq!(run(30, &config()), "\
+Config {
+    name: \"a, b) {\",
+    ports: [80, 443],
+    env: {
+        \"a\": (1, None),
+        \"b\": (2, Some('}')),
+    },
+    unit: (),
+    one: (1,),
+    empty: [],
+}");
+    }
+
+    #[test]
+    fn long() {
+        // longer than the lookahead
+        let inner = vec![1u8; 100];
+        let value = [&inner, &inner];
+        assert_eq!(run(1000, &value), format!("[\n    {inner:?},\n    {inner:?},\n]"));
+    }
+
+    #[test]
+    fn with_options() {
+        let mut out = String::new();
+        FormatterBuilder::new()
+            .debug_layout(true)
+            .width(12)
+            .indent_with("// ")
+            .pretty_indent(IndentStyle::Tab)
+            .with(&mut out, |f| [1, 2].fmt(f))
+            .unwrap();
+        assert_eq!(out, "// [1, 2]");
+
+        let mut out = String::new();
+        FormatterBuilder::new()
+            .debug_layout(true)
+            .width(8)
+            .indent_with("// ")
+            .pretty_indent(IndentStyle::Tab)
+            .with(&mut out, |f| [1, 2].fmt(f))
+            .unwrap();
+        assert_eq!(out, "// [\n// \t1,\n// \t2,\n// ]");
+    }
+}
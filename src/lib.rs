@@ -4,6 +4,50 @@
 use core::fmt::{self, Write, Formatter};
 pub use core::fmt::{Alignment};
 
+mod case;
+mod currency;
+mod decimal;
+mod duration;
+mod error;
+mod float;
+mod hexfloat;
+mod indent;
+mod layout;
+mod locale;
+mod number;
+mod pattern;
+mod post;
+mod prefix;
+mod pretty;
+mod printf;
+mod python;
+mod radix;
+mod round;
+mod special;
+mod typed;
+mod wrap;
+mod writer;
+
+pub use case::Case;
+pub use currency::{Currency, NegativeStyle, SymbolPosition};
+pub use decimal::Decimal;
+pub use duration::{DurationFmt, DurationStyle};
+pub use error::SpecError;
+pub use float::FloatStyle;
+pub use hexfloat::{HexFloat, ParseHexFloatError};
+pub use locale::Locale;
+pub use number::GroupSize;
+pub use pattern::{NumberPattern, NumberPatternError};
+pub use prefix::{Binary, Si};
+pub use pretty::IndentStyle;
+pub use printf::{printf, FormatTrait, PrintfArg, PrintfError};
+pub use python::{PyArg, PySpec, PySpecError, PyValue};
+pub use radix::{Radix, RadixInt};
+pub use round::RoundingMode;
+pub use special::SpecialValues;
+pub use typed::{AlignState, Aligned, TypedBuilder, Unaligned};
+pub use wrap::WrapOptions;
+
 /// The signedness of a [`Formatter`].
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Sign {
@@ -11,6 +55,13 @@ pub enum Sign {
     Plus,
     /// Represents the `-` flag.
     Minus,
+    /// Like printf `% d`, a space for non-negative numbers
+    ///
+    /// Formatting with the `+` flag, and replace the sign written by core into space.
+    ///
+    /// The first `+` written alone before any other char is taken as the sign,
+    /// so a leading `+` of text is replaced too, e.g `f.write_char('+')` then `x` is written as ` x`
+    Space,
 }
 
 // NOTE: 如果要添加更多字符, 是一个 break,
@@ -47,6 +98,12 @@ impl TryFrom<char> for Fill {
 }
 #[derive(Debug)]
 pub struct TryFromCharError(char);
+impl TryFromCharError {
+    /// Get the rejected char
+    pub fn as_char(&self) -> char {
+        self.0
+    }
+}
 impl fmt::Display for TryFromCharError {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
         write!(f, "unsupported fill char {:?}", self.0)
@@ -62,13 +119,30 @@ impl core::error::Error for TryFromCharError {
 ///
 #[doc = include_str!("../README.md")]
 #[derive(Debug, Default, Clone)]
-pub struct FormatterBuilder {
+pub struct FormatterBuilder<'a> {
     sign: Option<Sign>,
     sign_aware_zero_pad: bool,
     alternate: bool,
     fill_align: Option<(Option<Fill>, Alignment)>,
     width: Option<u16>,
     precision: Option<u16>,
+    grouping: Option<number::Grouping>,
+    locale: Option<Locale>,
+    int_precision: bool,
+    int_format: Option<FormatTrait>,
+    float_style: FloatStyle,
+    exponent_digits: u8,
+    exponent_sign: bool,
+    significant_digits: Option<u16>,
+    rounding: RoundingMode,
+    special_values: Option<SpecialValues<'a>>,
+    case: Option<Case>,
+    indent: Option<indent::Indent<'a>>,
+    indent_skip_first: bool,
+    pretty_indent: Option<IndentStyle>,
+    debug_layout: bool,
+    align_fields: bool,
+    wrap: Option<(u16, WrapOptions)>,
 }
 
 macro_rules! pack {
@@ -120,9 +194,56 @@ macro_rules! builder {
     };
 }
 
-impl FormatterBuilder {
+impl FormatterBuilder<'_> {
+    /// Format the closure output by the builder
+    ///
+    /// The float settings [`float_style`](FormatterBuilder::float_style),
+    /// [`significant_digits`](FormatterBuilder::significant_digits), [`rounding`](FormatterBuilder::rounding)
+    /// and [`special_values`](FormatterBuilder::special_values) are ignored,
+    /// they only apply through [`float`](FormatterBuilder::float)
+    #[inline]
+    pub fn with<W, F>(&self, writer: W, f: F) -> fmt::Result
+    where
+        W: Write,
+        F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+    {
+        if self.wrap.is_some() {
+            return wrap::with(self, writer, f);
+        }
+        if self.debug_layout {
+            return layout::with(self, writer, f);
+        }
+        if self.is_post() {
+            return post::with(self, writer, f);
+        }
+        if self.indent.is_some() || self.pretty_style().is_some() || self.align_style() {
+            let mut writer = pretty::AlignFields::new(self.indent_writer(writer), self.align_style());
+            self.with_plain(pretty::PrettyIndent::new(&mut writer, self.pretty_style()), f)?;
+            return writer.finish();
+        }
+        self.with_plain(writer, f)
+    }
+
+    fn indent_writer<W: Write>(&self, writer: W) -> indent::IndentWriter<'_, W> {
+        indent::IndentWriter::new(writer, self.indent, !self.indent_skip_first)
+    }
+
+    /// [`with`](FormatterBuilder::with) without post-processing
+    #[inline]
+    fn with_plain<W, F>(&self, writer: W, f: F) -> fmt::Result
+    where
+        W: Write,
+        F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+    {
+        if self.sign == Some(Sign::Space) {
+            let fill = self.fill_align.and_then(|(fill, _)| fill).map_or(' ', Fill::as_char);
+            return self.with_core(writer::SignSpace::new(writer, fill), f);
+        }
+        self.with_core(writer, f)
+    }
+
     #[inline]
-    pub fn with<W, F>(&self, mut writer: W, f: F) -> fmt::Result
+    fn with_core<W, F>(&self, mut writer: W, f: F) -> fmt::Result
     where
         W: Write,
         F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
@@ -146,7 +267,7 @@ impl FormatterBuilder {
                 None => "",
             ],
             sign [
-                Some(Sign::Plus) => "+",
+                Some(Sign::Plus | Sign::Space) => "+",
                 Some(Sign::Minus) => "-",
                 None => "",
             ],
@@ -172,7 +293,7 @@ impl FormatterBuilder {
     }
 }
 
-impl FormatterBuilder {
+impl FormatterBuilder<'_> {
     pub fn new() -> Self {
         Self::default()
     }
@@ -208,7 +329,125 @@ impl FormatterBuilder {
         b
     }
 
-    /// Format like `{:+}` and `{:-}`
+    /// Rebuild from [`Formatter`], like [`from_formatter_lossy`](FormatterBuilder::from_formatter_lossy),
+    /// but returns error when fill char, width or precision is unsupported
+    ///
+    /// DebugHex (`x?` `X?`) still lose
+    pub fn try_from_formatter(f: &Formatter<'_>) -> Result<Self, SpecError> {
+        let mut b = Self::new();
+
+        if f.sign_plus() {
+            b.sign(Sign::Plus);
+        } else if f.sign_minus() {
+            b.sign(Sign::Minus);
+        }
+
+        b.sign_aware_zero_pad(f.sign_aware_zero_pad())
+            .alternate(f.alternate())
+            .try_width(f.width())?
+            .try_precision(f.precision())?;
+
+        if let Some(align) = f.align() {
+            b.align(align).try_fill(f.fill())?;
+        }
+
+        Ok(b)
+    }
+
+    /// Check the builder specification, the first conflicting flags are reported
+    ///
+    /// - `align` is overridden by `sign_aware_zero_pad`, and by `debug_layout` where width is the line width
+    /// - `precision` is overridden by `significant_digits` in [`float`](FormatterBuilder::float)
+    /// - `debug_layout` and `align_fields` are overridden by `wrap`, which collapses their whitespaces
+    ///
+    /// [`SpecError::FillWithoutAlignment`] is not reported, fill without align can't be set,
+    /// see [`try_fill`](FormatterBuilder::try_fill)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut b = FormatterBuilder::new();
+    /// assert_eq!(b.validate(), Ok(()));
+    ///
+    /// b.align(Alignment::Left).sign_aware_zero_pad(true);
+    /// assert_eq!(b.validate(), Err(SpecError::ConflictingFlags("align", "sign_aware_zero_pad")));
+    ///
+    /// let mut b = FormatterBuilder::new();
+    /// b.debug_layout(true).wrap(40, None);
+    /// assert_eq!(b.validate(), Err(SpecError::ConflictingFlags("debug_layout", "wrap")));
+    /// ```
+    pub fn validate(&self) -> Result<(), SpecError> {
+        let align = self.fill_align.is_some();
+        let wrap = self.wrap.is_some();
+        let conflicts = [
+            (align && self.sign_aware_zero_pad, "align", "sign_aware_zero_pad"),
+            (align && self.debug_layout, "align", "debug_layout"),
+            (self.precision.is_some() && self.significant_digits.is_some(), "precision", "significant_digits"),
+            (self.debug_layout && wrap, "debug_layout", "wrap"),
+            (self.align_fields && wrap, "align_fields", "wrap"),
+        ];
+        match conflicts.into_iter().find(|&(conflict, ..)| conflict) {
+            Some((_, first, second)) => Err(SpecError::ConflictingFlags(first, second)),
+            None => Ok(()),
+        }
+    }
+
+    /// Like [`fill`](FormatterBuilder::fill), but accept any char and never panics
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut b = FormatterBuilder::new();
+    /// assert_eq!(b.try_fill('0').unwrap_err(), SpecError::FillWithoutAlignment);
+    /// b.align(Alignment::Left);
+    /// assert_eq!(b.try_fill('*').unwrap_err().unsupported_fill(), Some('*'));
+    /// assert!(b.try_fill('0').is_ok());
+    /// ```
+    pub fn try_fill(&mut self, fill: char) -> Result<&mut Self, SpecError> {
+        let fill = Fill::try_from(fill)?;
+        let (fill_char, _) = self.fill_align.as_mut()
+            .ok_or(SpecError::FillWithoutAlignment)?;
+        *fill_char = Some(fill);
+        Ok(self)
+    }
+
+    /// Like [`width`](FormatterBuilder::width), but accept [`usize`] and never truncates
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut b = FormatterBuilder::new();
+    /// assert!(b.try_width(3).is_ok());
+    /// assert_eq!(b.try_width(70000).unwrap_err().width_overflow(), Some(70000));
+    /// ```
+    pub fn try_width(&mut self, width: impl Into<Option<usize>>) -> Result<&mut Self, SpecError> {
+        let width = width.into()
+            .map(|width| u16::try_from(width).map_err(|_| SpecError::WidthOverflow(width)))
+            .transpose()?;
+        Ok(self.width(width))
+    }
+
+    /// Like [`precision`](FormatterBuilder::precision), but accept [`usize`] and never truncates
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut b = FormatterBuilder::new();
+    /// assert!(b.try_precision(3).is_ok());
+    /// assert_eq!(b.try_precision(70000).unwrap_err().precision_overflow(), Some(70000));
+    /// ```
+    pub fn try_precision(&mut self, precision: impl Into<Option<usize>>) -> Result<&mut Self, SpecError> {
+        let precision = precision.into()
+            .map(|precision| u16::try_from(precision).map_err(|_| SpecError::PrecisionOverflow(precision)))
+            .transpose()?;
+        Ok(self.precision(precision))
+    }
+
+    /// Format like `{:+}` and `{:-}`, or printf `% d`
     ///
     /// # Examples
     ///
@@ -222,6 +461,16 @@ impl FormatterBuilder {
     /// }).unwrap();
     /// assert_eq!(writter, "+2");
     /// ```
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::Display;
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().sign(Sign::Space).sign_aware_zero_pad(true).width(5).with(&mut writter, |f| {
+    ///     2i32.fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, " 0002");
+    /// ```
     pub fn sign(&mut self, sign: impl Into<Option<Sign>>) -> &mut Self {
         self.sign = sign.into();
         self
@@ -265,7 +514,8 @@ impl FormatterBuilder {
     ///
     /// # Panics
     ///
-    /// - panic when [`align`](FormatterBuilder::align) is unset
+    /// - panic when [`align`](FormatterBuilder::align) is unset,
+    ///   use [`fill_align`](FormatterBuilder::fill_align) or [`TypedBuilder`] to avoid it
     ///
     /// # Examples
     ///
@@ -281,11 +531,31 @@ impl FormatterBuilder {
     #[track_caller]
     pub fn fill(&mut self, fill: impl Into<Option<Fill>>) -> &mut Self {
         if let Some(fill_char) = fill.into() {
-            self.fill_align.as_mut().expect(".fill must setted align").0 = Some(fill_char);
+            let (_, align) = self.fill_align.expect(".fill must setted align");
+            self.fill_align(fill_char, align);
         }
         self
     }
 
+    /// Format like `{:0>}` `{:<}` etc, set fill and align at the same time
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// # use Alignment::*;
+    /// # let writter = String::new();
+    /// FormatterBuilder::new().fill_align(Fill::Zero, Right).with(writter, |f| {
+    ///     assert_eq!(f.fill(), '0');
+    ///     assert_eq!(f.align(), Some(Right));
+    ///     Ok(())
+    /// }).unwrap();
+    /// ```
+    pub fn fill_align(&mut self, fill: impl Into<Option<Fill>>, align: Alignment) -> &mut Self {
+        self.fill_align = Some((fill.into(), align));
+        self
+    }
+
     /// Format like `{:<}` and  `{:^}` and `{:>}`
     ///
     /// # Examples
@@ -345,6 +615,151 @@ impl FormatterBuilder {
     }
 }
 
+/// Post-processing settings
+///
+/// When any of these is set, [`with`](FormatterBuilder::with) formats the closure
+/// without width, fill and align, post-processes the output,
+/// then pads the whole output to the width.
+///
+/// Numbers are right-aligned and other output is left-aligned by default, like core.
+/// The output is captured into a small buffer to measure it,
+/// longer output is streamed, but it's an error if it still needs to pad before it
+impl FormatterBuilder<'_> {
+    fn is_post(&self) -> bool {
+        self.grouping.is_some()
+            || self.locale.is_some()
+            || self.int_precision && self.precision.is_some()
+            || self.case.is_some()
+    }
+
+    /// Group the integer digits in the output, like `1,234,567`
+    ///
+    /// Only the number at the start of the output, after an optional sign, is rewritten,
+    /// numbers later in the output like `Order 12345` are not.
+    /// Only its decimal integer digits are grouped, digits after `.` or letters are not.
+    /// Zeros of [`sign_aware_zero_pad`](FormatterBuilder::sign_aware_zero_pad) are grouped too,
+    /// a run of more than 320 digits after the leading zeros is not grouped
+    ///
+    /// Padding before a closure output longer than 1024 bytes is not written, the output is streamed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::Display;
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().grouping(',', 3).precision(2).with(&mut writter, |f| {
+    ///     1234567.891.fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "1,234,567.89");
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().grouping(',', GroupSize::INDIAN).width(12).with(&mut writter, |f| {
+    ///     (-1234567).fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "  -12,34,567");
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().grouping('_', 3).sign_aware_zero_pad(true).width(8).with(&mut writter, |f| {
+    ///     1234.fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "0_001_234");
+    /// ```
+    pub fn grouping(
+        &mut self,
+        separator: impl Into<Option<char>>,
+        group_size: impl Into<GroupSize>,
+    ) -> &mut Self {
+        let size = group_size.into();
+        self.grouping = separator.into().map(|separator| number::Grouping { separator, size });
+        self
+    }
+
+    /// Precision is the minimum digits of integers, like printf `%.5d`
+    ///
+    /// Zeros are inserted after the sign and radix prefix,
+    /// output of only decimal digits after them is taken as an integer,
+    /// or hex digits by [`int_format`](FormatterBuilder::int_format)
+    ///
+    /// Padding before a closure output longer than 1024 bytes is not written, the output is streamed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::{Display, LowerHex};
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().int_precision(true).precision(5).width(8).with(&mut writter, |f| {
+    ///     Display::fmt(&-42, f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "  -00042");
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().int_precision(true).precision(4).alternate(true)
+    ///     .int_format(FormatTrait::LowerHex)
+    ///     .with(&mut writter, |f| LowerHex::fmt(&0xf, f))
+    ///     .unwrap();
+    /// assert_eq!(writter, "0x000f");
+    /// ```
+    pub fn int_precision(&mut self, int_precision: bool) -> &mut Self {
+        self.int_precision = int_precision;
+        self
+    }
+
+    /// Formatting trait of the integers of [`int_precision`](FormatterBuilder::int_precision),
+    /// hex digits are only taken as an integer by [`FormatTrait::LowerHex`] and [`FormatTrait::UpperHex`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::Display;
+    /// let mut b = FormatterBuilder::new();
+    /// b.int_precision(true).precision(6);
+    ///
+    /// let mut writter = String::new();
+    /// b.with(&mut writter, |f| "cafe".fmt(f)).unwrap();
+    /// assert_eq!(writter, "cafe");
+    ///
+    /// let mut writter = String::new();
+    /// b.int_format(FormatTrait::UpperHex).with(&mut writter, |f| "CAFE".fmt(f)).unwrap();
+    /// assert_eq!(writter, "00CAFE");
+    /// ```
+    pub fn int_format(&mut self, format_trait: impl Into<Option<FormatTrait>>) -> &mut Self {
+        self.int_format = format_trait.into();
+        self
+    }
+
+    /// Rewrite the number at the start of the output by the locale,
+    /// decimal separator, grouping, minus sign and digits
+    ///
+    /// The grouping of locale is overridden by [`grouping`](FormatterBuilder::grouping)
+    ///
+    /// Padding before a closure output longer than 1024 bytes is not written, the output is streamed
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::Display;
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().locale(Locale::DE_DE).precision(1).with(&mut writter, |f| {
+    ///     1234.5.fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "1.234,5");
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().locale(Locale::HI_IN).with(&mut writter, |f| {
+    ///     1234567.fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "१२,३४,५६७");
+    /// ```
+    pub fn locale(&mut self, locale: impl Into<Option<Locale>>) -> &mut Self {
+        self.locale = locale.into();
+        self
+    }
+}
+
 struct FormatWith<F>(core::cell::Cell<Option<F>>)
 where
     F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
@@ -458,7 +873,7 @@ mod tests {
     #[test]
     fn sign() {
         // Sign::Minus unused
-        for (sign, exp) in [(Sign::Plus, "+1")] {
+        for (sign, exp) in [(Sign::Plus, "+1"), (Sign::Space, " 1")] {
             let mut out = String::new();
             FormatterBuilder::new()
                 .sign(sign)
@@ -470,6 +885,80 @@ mod tests {
         }
     }
 
+    #[test]
+    fn sign_space() {
+        fn run(sazp: bool, width: Option<u16>, n: &dyn fmt::Display) -> String {
+            let mut out = String::new();
+            FormatterBuilder::new()
+                .sign(Sign::Space)
+                .sign_aware_zero_pad(sazp)
+                .width(width)
+                .with(&mut out, |f| n.fmt(f))
+                .unwrap();
+            out
+        }
+        assert_eq!(run(false, None, &1), " 1");
+        assert_eq!(run(false, None, &-1), "-1");
+        assert_eq!(run(false, Some(4), &1), "   1");
+        assert_eq!(run(false, Some(4), &0), "   0");
+        assert_eq!(run(true, Some(5), &42), " 0042");
+        assert_eq!(run(true, Some(5), &-42), "-0042");
+        assert_eq!(run(true, Some(6), &1.5), " 001.5");
+        assert_eq!(run(true, Some(6), &-1.5), "-001.5");
+        assert_eq!(run(false, Some(6), &1.5), "   1.5");
+
+        // only the leading sign is replaced
+        let mut out = String::new();
+        FormatterBuilder::new().sign(Sign::Space).with(&mut out, |f| {
+            fmt::Display::fmt(&1, f)?;
+            f.write_char('+')?;
+            write!(f, "{:+}", 5)
+        }).unwrap();
+        assert_eq!(out, " 1++5");
+
+        // a leading `+` of text is taken as the sign, unless written with other chars
+        let mut out = String::new();
+        FormatterBuilder::new().sign(Sign::Space).with(&mut out, |f| {
+            f.write_char('+')?;
+            f.write_str("x +y")
+        }).unwrap();
+        assert_eq!(out, " x +y");
+
+        let mut out = String::new();
+        FormatterBuilder::new().sign(Sign::Space).with(&mut out, |f| f.write_str("+x")).unwrap();
+        assert_eq!(out, "+x");
+
+        let mut out = String::new();
+        FormatterBuilder::new().sign(Sign::Space).width(4).align(Alignment::Right).fill(Fill::Zero).with(&mut out, |f| {
+            fmt::Display::fmt(&1, f)?;
+            f.write_char('+')
+        }).unwrap();
+        assert_eq!(out, "00 1+");
+    }
+
+    #[test]
+    fn validate() {
+        let conflict = |b: &mut FormatterBuilder<'_>| b.validate().unwrap_err().conflicting_flags().unwrap();
+        assert_eq!(conflict(FormatterBuilder::new().align(Left).debug_layout(true)), ("align", "debug_layout"));
+        assert_eq!(conflict(FormatterBuilder::new().precision(2).significant_digits(3)), ("precision", "significant_digits"));
+        assert_eq!(conflict(FormatterBuilder::new().align_fields(true).wrap(20, None)), ("align_fields", "wrap"));
+        assert!(FormatterBuilder::new().align(Left).width(5).precision(2).wrap(20, None).validate().is_ok());
+    }
+
+    #[test]
+    fn try_from_formatter() {
+        struct Foo(Result<(), SpecError>);
+        impl fmt::Display for Foo {
+            fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+                let b = FormatterBuilder::try_from_formatter(f).map(drop);
+                assert_eq!(b, self.0);
+                Ok(())
+            }
+        }
+        let _ = format!("{:0^-#2.1}", Foo(Ok(())));
+        let _ = format!("{:*^2}", Foo(Err(SpecError::UnsupportedFill('*'))));
+    }
+
     #[test]
     fn core_default_fill() {
         struct Foo;
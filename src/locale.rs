@@ -0,0 +1,151 @@
+use crate::GroupSize;
+
+/// Number symbols of a locale, used by [`FormatterBuilder::locale`](crate::FormatterBuilder::locale)
+///
+/// Common locales are built-in, see [`Locale::ALL`]
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// let custom = Locale::new("x-custom", ',')
+///     .with_grouping('\'', GroupSize::THOUSANDS)
+///     .with_minus("\u{2212}");
+/// assert_eq!(custom.decimal_separator(), ',');
+/// assert_eq!(Locale::lookup("de_de"), Some(Locale::DE_DE));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Locale {
+    tag: &'static str,
+    decimal: char,
+    grouping: Option<(char, GroupSize)>,
+    minus: &'static str,
+    digits: Option<[char; 10]>,
+}
+
+const fn digits_from(zero: char) -> [char; 10] {
+    let mut digits = [zero; 10];
+    let mut i = 0;
+    while i < 10 {
+        digits[i] = char::from_u32(zero as u32 + i as u32).unwrap();
+        i += 1;
+    }
+    digits
+}
+
+const NBSP: char = '\u{a0}';
+const NNBSP: char = '\u{202f}';
+const ARABIC: Locale = Locale::new("ar", '\u{66b}')
+    .with_grouping('\u{66c}', GroupSize::THOUSANDS)
+    .with_minus("\u{61c}-");
+
+impl Locale {
+    pub const EN_US: Self = Self::new("en-US", '.').with_grouping(',', GroupSize::THOUSANDS);
+    pub const EN_GB: Self = Self::new("en-GB", '.').with_grouping(',', GroupSize::THOUSANDS);
+    pub const EN_IN: Self = Self::new("en-IN", '.').with_grouping(',', GroupSize::INDIAN);
+    pub const DE_DE: Self = Self::new("de-DE", ',').with_grouping('.', GroupSize::THOUSANDS);
+    pub const DE_CH: Self = Self::new("de-CH", '.').with_grouping('\u{2019}', GroupSize::THOUSANDS);
+    pub const FR_FR: Self = Self::new("fr-FR", ',').with_grouping(NNBSP, GroupSize::THOUSANDS);
+    pub const ES_ES: Self = Self::new("es-ES", ',').with_grouping('.', GroupSize::THOUSANDS);
+    pub const IT_IT: Self = Self::new("it-IT", ',').with_grouping('.', GroupSize::THOUSANDS);
+    pub const NL_NL: Self = Self::new("nl-NL", ',').with_grouping('.', GroupSize::THOUSANDS);
+    pub const PT_BR: Self = Self::new("pt-BR", ',').with_grouping('.', GroupSize::THOUSANDS);
+    pub const RU_RU: Self = Self::new("ru-RU", ',').with_grouping(NBSP, GroupSize::THOUSANDS);
+    pub const PL_PL: Self = Self::new("pl-PL", ',').with_grouping(NBSP, GroupSize::THOUSANDS);
+    pub const SV_SE: Self = Self::new("sv-SE", ',')
+        .with_grouping(NBSP, GroupSize::THOUSANDS)
+        .with_minus("\u{2212}");
+    pub const JA_JP: Self = Self::new("ja-JP", '.').with_grouping(',', GroupSize::THOUSANDS);
+    pub const ZH_CN: Self = Self::new("zh-CN", '.').with_grouping(',', GroupSize::THOUSANDS);
+    pub const KO_KR: Self = Self::new("ko-KR", '.').with_grouping(',', GroupSize::THOUSANDS);
+    pub const TH_TH: Self = Self::new("th-TH", '.').with_grouping(',', GroupSize::THOUSANDS);
+    /// Hindi with Devanagari digits
+    pub const HI_IN: Self = Self::new("hi-IN", '.')
+        .with_grouping(',', GroupSize::INDIAN)
+        .with_digits(digits_from('\u{966}'));
+    /// Bengali with Bengali digits
+    pub const BN_BD: Self = Self::new("bn-BD", '.')
+        .with_grouping(',', GroupSize::INDIAN)
+        .with_digits(digits_from('\u{9e6}'));
+    /// Arabic with Arabic-Indic digits
+    pub const AR_EG: Self = ARABIC.with_tag("ar-EG").with_digits(digits_from('\u{660}'));
+    /// Persian with Extended Arabic-Indic digits
+    pub const FA_IR: Self = ARABIC.with_tag("fa-IR")
+        .with_minus("\u{200e}\u{2212}")
+        .with_digits(digits_from('\u{6f0}'));
+
+    /// All built-in locales
+    pub const ALL: &'static [Self] = &[
+        Self::EN_US, Self::EN_GB, Self::EN_IN, Self::DE_DE, Self::DE_CH,
+        Self::FR_FR, Self::ES_ES, Self::IT_IT, Self::NL_NL, Self::PT_BR,
+        Self::RU_RU, Self::PL_PL, Self::SV_SE, Self::JA_JP, Self::ZH_CN,
+        Self::KO_KR, Self::TH_TH, Self::HI_IN, Self::BN_BD, Self::AR_EG,
+        Self::FA_IR,
+    ];
+
+    /// Locale without grouping, minus sign is `-` and digits are ASCII
+    pub const fn new(tag: &'static str, decimal_separator: char) -> Self {
+        Self {
+            tag,
+            decimal: decimal_separator,
+            grouping: None,
+            minus: "-",
+            digits: None,
+        }
+    }
+
+    const fn with_tag(mut self, tag: &'static str) -> Self {
+        self.tag = tag;
+        self
+    }
+
+    pub const fn with_grouping(mut self, separator: char, size: GroupSize) -> Self {
+        self.grouping = Some((separator, size));
+        self
+    }
+
+    pub const fn with_minus(mut self, minus: &'static str) -> Self {
+        self.minus = minus;
+        self
+    }
+
+    /// Digits from zero to nine
+    pub const fn with_digits(mut self, digits: [char; 10]) -> Self {
+        self.digits = Some(digits);
+        self
+    }
+
+    /// Find built-in locale, like `de-DE` `de_de`
+    pub fn lookup(tag: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|locale| {
+            locale.tag.len() == tag.len() && locale.tag.bytes().zip(tag.bytes()).all(|(a, b)| {
+                a.eq_ignore_ascii_case(&b) || a == b'-' && b == b'_'
+            })
+        })
+    }
+
+    pub fn tag(&self) -> &'static str {
+        self.tag
+    }
+
+    pub fn decimal_separator(&self) -> char {
+        self.decimal
+    }
+
+    pub fn grouping_separator(&self) -> Option<char> {
+        self.grouping.map(|(separator, _)| separator)
+    }
+
+    pub fn group_size(&self) -> Option<GroupSize> {
+        self.grouping.map(|(_, size)| size)
+    }
+
+    pub fn minus_sign(&self) -> &'static str {
+        self.minus
+    }
+
+    /// Digits from zero to nine, [`None`] is ASCII digits
+    pub fn digits(&self) -> Option<[char; 10]> {
+        self.digits
+    }
+}
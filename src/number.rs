@@ -0,0 +1,336 @@
+//! Internal number layout, digit grouping and padding in software
+//!
+//! The number body is written twice, first measure, then write it out,
+//! so nothing is buffered
+
+use core::{fmt::{self, Formatter, Write}, iter};
+
+use crate::Alignment;
+
+/// Where the padding goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PadAlign {
+    Left,
+    Right,
+    Center,
+    /// Padding after sign and prefix, like `{:0}` and python `=`
+    AfterSign,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Pad {
+    pub fill: char,
+    pub align: PadAlign,
+    pub width: usize,
+}
+
+impl Pad {
+    /// Width, fill and align of the formatter, `{:0}` is `'0'` fill and [`PadAlign::AfterSign`]
+    pub(crate) fn from_formatter(f: &Formatter<'_>, default: PadAlign) -> Self {
+        let (fill, align) = match f.align() {
+            _ if f.sign_aware_zero_pad() => ('0', PadAlign::AfterSign),
+            Some(Alignment::Left) => (f.fill(), PadAlign::Left),
+            Some(Alignment::Right) => (f.fill(), PadAlign::Right),
+            Some(Alignment::Center) => (f.fill(), PadAlign::Center),
+            None => (f.fill(), default),
+        };
+        Self { fill, align, width: f.width().unwrap_or(0) }
+    }
+
+    /// Count of fill chars before and after the output of `len` chars,
+    /// [`PadAlign::AfterSign`] is before
+    pub(crate) fn split(&self, len: usize) -> (usize, usize) {
+        let padding = self.width.saturating_sub(len);
+        match self.align {
+            PadAlign::Left => (0, padding),
+            PadAlign::Center => (padding / 2, padding - padding / 2),
+            PadAlign::Right | PadAlign::AfterSign => (padding, 0),
+        }
+    }
+
+    pub(crate) fn write_fill(&self, w: &mut dyn Write, n: usize) -> fmt::Result {
+        (0..n).try_for_each(|_| w.write_char(self.fill))
+    }
+}
+
+/// Sizes of digit groups, the rightmost group and the other groups
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// assert_eq!(GroupSize::from(3), GroupSize::THOUSANDS);
+/// assert_eq!(GroupSize::irregular(3, 2), GroupSize::INDIAN);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupSize {
+    first: u8,
+    rest: u8,
+}
+
+impl GroupSize {
+    /// Groups of three digits, like `1,234,567`
+    pub const THOUSANDS: Self = Self::new(3);
+
+    /// Indian style groups, like `12,34,567`
+    pub const INDIAN: Self = Self::irregular(3, 2);
+
+    /// All groups are the same size
+    ///
+    /// # Panics
+    ///
+    /// - panic when size is zero
+    #[track_caller]
+    pub const fn new(size: u8) -> Self {
+        Self::irregular(size, size)
+    }
+
+    /// The rightmost group is `first` digits, and the other groups are `rest` digits
+    ///
+    /// # Panics
+    ///
+    /// - panic when any size is zero
+    #[track_caller]
+    pub const fn irregular(first: u8, rest: u8) -> Self {
+        assert!(first != 0 && rest != 0, "group size must be non-zero");
+        Self { first, rest }
+    }
+
+    /// Size of the rightmost group
+    pub fn first(&self) -> u8 {
+        self.first
+    }
+
+    /// Size of the other groups
+    pub fn rest(&self) -> u8 {
+        self.rest
+    }
+}
+
+impl From<u8> for GroupSize {
+    #[track_caller]
+    fn from(size: u8) -> Self {
+        Self::new(size)
+    }
+}
+
+/// Digit grouping separator and group sizes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Grouping {
+    pub separator: char,
+    pub size: GroupSize,
+}
+
+impl Grouping {
+    fn sizes(&self) -> impl Iterator<Item = usize> {
+        iter::once(self.size.first.into()).chain(iter::repeat(self.size.rest.into()))
+    }
+
+    /// Is there a separator before the digit, `left` is count of the digit and digits after it
+    pub(crate) fn is_boundary(&self, left: usize) -> bool {
+        let (first, rest) = (usize::from(self.size.first), usize::from(self.size.rest));
+        left == first || left > first && (left - first).is_multiple_of(rest)
+    }
+
+    /// Count of separators in the digits
+    pub(crate) fn separators(&self, digits: usize) -> usize {
+        let mut sum = 0;
+        self.sizes().take_while(|&size| {
+            sum += size;
+            sum < digits
+        }).count()
+    }
+
+    /// Count of digits when padding zeros into the groups, like CPython
+    ///
+    /// e.g `format(1234, "08,")` is `0,001,234`
+    pub(crate) fn zero_fill(&self, digits: usize, min_width: usize) -> usize {
+        let mut remaining = digits;
+        let mut min_width = min_width as isize;
+        let mut total = 0;
+
+        for size in self.sizes() {
+            let len = size.min(remaining.max(min_width.max(1) as usize));
+            remaining = remaining.saturating_sub(len);
+            total += len;
+            min_width -= len as isize;
+
+            if remaining == 0 && min_width <= 0 {
+                break;
+            }
+            min_width -= 1;
+        }
+        total
+    }
+}
+
+/// Measure of a number body, body is unsigned integer digits and rest, e.g `1234.5e+03`
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Measure {
+    radix: u32,
+    in_int: bool,
+    /// Count of chars in body
+    pub chars: usize,
+    /// Count of leading digits in body
+    pub int_digits: usize,
+    /// Is any nonzero digit in body
+    pub nonzero: bool,
+}
+
+impl Write for Measure {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().try_for_each(|ch| self.write_char(ch))
+    }
+
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        let is_digit = ch.is_digit(self.radix);
+        self.in_int &= is_digit;
+        self.int_digits += self.in_int as usize;
+        self.nonzero |= is_digit && ch != '0';
+        self.chars += 1;
+        Ok(())
+    }
+}
+
+impl Measure {
+    pub(crate) fn new(
+        radix: u32,
+        body: &dyn Fn(&mut dyn Write) -> fmt::Result,
+    ) -> Result<Self, fmt::Error> {
+        let mut measure = Self { radix, in_int: true, ..Self::default() };
+        body(&mut measure)?;
+        Ok(measure)
+    }
+
+    /// Write the number, `sign prefix int_digits rest`, with grouping and padding
+    ///
+    /// When fill is `'0'` and align is [`PadAlign::AfterSign`], zeros are grouped
+    pub(crate) fn write(
+        &self,
+        w: &mut dyn Write,
+        sign: &str,
+        prefix: &str,
+        grouping: Option<&Grouping>,
+        pad: &Pad,
+        body: &dyn Fn(&mut dyn Write) -> fmt::Result,
+    ) -> fmt::Result {
+        let head = sign.chars().count() + prefix.chars().count();
+        let rest = self.chars - self.int_digits;
+        let mut digits = self.int_digits;
+
+        if let Some(grouping) = grouping.filter(|_| {
+            pad.fill == '0' && pad.align == PadAlign::AfterSign
+        }) {
+            digits = grouping.zero_fill(digits, pad.width.saturating_sub(head + rest));
+        }
+        let zeros = digits - self.int_digits;
+        let separators = grouping.map_or(0, |grouping| grouping.separators(digits));
+        let padding = pad.width.saturating_sub(head + rest + digits + separators);
+
+        let (pre, mid, post) = match pad.align {
+            PadAlign::Left => (0, 0, padding),
+            PadAlign::Right => (padding, 0, 0),
+            PadAlign::Center => (padding / 2, 0, padding - padding / 2),
+            PadAlign::AfterSign => (0, padding, 0),
+        };
+
+        pad.write_fill(w, pre)?;
+        w.write_str(sign)?;
+        w.write_str(prefix)?;
+        pad.write_fill(w, mid)?;
+
+        match grouping {
+            Some(grouping) => {
+                let mut w = GroupWriter { inner: w, grouping, radix: self.radix, total: digits, left: digits };
+                (0..zeros).try_for_each(|_| w.write_char('0'))?;
+                body(&mut w)?;
+            },
+            None => body(w)?,
+        }
+
+        pad.write_fill(w, post)
+    }
+}
+
+/// Insert separators into leading digits
+struct GroupWriter<'a, W: ?Sized> {
+    inner: &'a mut W,
+    grouping: &'a Grouping,
+    radix: u32,
+    total: usize,
+    /// Count of remaining leading digits
+    left: usize,
+}
+
+impl<W: Write + ?Sized> Write for GroupWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut chars = s.char_indices();
+        while self.left != 0 {
+            let Some((i, ch)) = chars.next() else { return Ok(()) };
+            if !ch.is_digit(self.radix) {
+                self.left = 0;
+                return self.inner.write_str(&s[i..]);
+            }
+            if self.left != self.total && self.grouping.is_boundary(self.left) {
+                self.inner.write_char(self.grouping.separator)?;
+            }
+            self.inner.write_char(ch)?;
+            self.left -= 1;
+        }
+        self.inner.write_str(chars.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const THOUSANDS: Grouping = Grouping { separator: ',', size: GroupSize::THOUSANDS };
+    const INDIAN: Grouping = Grouping { separator: ',', size: GroupSize::INDIAN };
+
+    fn run(grouping: Option<&Grouping>, pad: Pad, sign: &str, body: &str) -> String {
+        let body = |w: &mut dyn Write| w.write_str(body);
+        let mut out = String::new();
+        Measure::new(10, &body).unwrap()
+            .write(&mut out, sign, "", grouping, &pad, &body)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn grouping() {
+        let pad = Pad { fill: ' ', align: PadAlign::Right, width: 0 };
+        assert_eq!(run(Some(&THOUSANDS), pad, "", "1"), "1");
+        assert_eq!(run(Some(&THOUSANDS), pad, "", "123"), "123");
+        assert_eq!(run(Some(&THOUSANDS), pad, "-", "1234.5678"), "-1,234.5678");
+        assert_eq!(run(Some(&THOUSANDS), pad, "", "1234567"), "1,234,567");
+        assert_eq!(run(Some(&INDIAN), pad, "", "123456789"), "12,34,56,789");
+        assert_eq!(run(Some(&INDIAN), pad, "", "1234"), "1,234");
+        assert_eq!(run(Some(&THOUSANDS), pad, "", "inf"), "inf");
+    }
+
+    #[test]
+    fn padding() {
+        let pad = |align, width| Pad { fill: '*', align, width };
+        assert_eq!(run(Some(&THOUSANDS), pad(PadAlign::Left, 8), "-", "1234"), "-1,234**");
+        assert_eq!(run(Some(&THOUSANDS), pad(PadAlign::Right, 8), "-", "1234"), "**-1,234");
+        assert_eq!(run(Some(&THOUSANDS), pad(PadAlign::Center, 9), "-", "1234"), "*-1,234**");
+        assert_eq!(run(Some(&THOUSANDS), pad(PadAlign::AfterSign, 8), "-", "1234"), "-**1,234");
+        assert_eq!(run(None, pad(PadAlign::Right, 3), "-", "1234"), "-1234");
+    }
+
+    #[test]
+    fn zero_fill() {
+        let pad = |width| Pad { fill: '0', align: PadAlign::AfterSign, width };
+        let cases = [
+            (5, "1,234"), (6, "01,234"), (7, "001,234"),
+            (8, "0,001,234"), (9, "0,001,234"), (10, "00,001,234"),
+        ];
+        for (width, exp) in cases {
+            assert_eq!(run(Some(&THOUSANDS), pad(width), "", "1234"), exp);
+        }
+        assert_eq!(run(Some(&THOUSANDS), pad(10), "-", "1234.5"), "-001,234.5");
+        assert_eq!(run(None, pad(7), "-", "1234.5"), "-1234.5");
+        assert_eq!(run(None, pad(8), "-", "1234.5"), "-01234.5");
+    }
+}
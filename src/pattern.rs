@@ -0,0 +1,468 @@
+//! Excel and .NET custom numeric format patterns, like `#,##0.00;(#,##0.00);"-"`
+
+use core::fmt::{self, Display, Formatter, Write};
+
+use crate::{
+    number::{Pad, PadAlign},
+    post::{ArrayBuf, Count, Sink},
+    FormatterBuilder,
+};
+
+/// Maximum fraction digit placeholders in a section
+const MAX_FRACTION: usize = 100;
+
+/// Capacity of the digits buffer, digits of [`f64::MAX`], the point and the fraction
+const DIGITS: usize = 310 + MAX_FRACTION;
+
+/// Error of [`NumberPattern::parse`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum NumberPatternError {
+    /// More than 3 sections
+    TooManySections,
+    /// Quote without closing quote
+    UnterminatedQuote,
+    /// `\` at the end of pattern
+    UnterminatedEscape,
+    /// More than 100 fraction digit placeholders in a section
+    TooManyDigits,
+}
+
+impl fmt::Display for NumberPatternError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::TooManySections => "more than 3 sections in number pattern",
+            Self::UnterminatedQuote => "unterminated quote in number pattern",
+            Self::UnterminatedEscape => "unterminated escape in number pattern",
+            Self::TooManyDigits => "too many fraction digits in number pattern",
+        })
+    }
+}
+
+impl core::error::Error for NumberPatternError {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+    /// `0` or `#`, is `0`
+    Digit(bool),
+    Point,
+    Comma,
+    Percent,
+    Permille,
+    Separator,
+    Literal(&'a str),
+}
+
+/// Next token of the pattern, advance the pattern
+fn next_token<'a>(s: &mut &'a str) -> Result<Option<Token<'a>>, NumberPatternError> {
+    let Some(ch) = s.chars().next() else {
+        return Ok(None);
+    };
+    let rest = &s[ch.len_utf8()..];
+
+    let (token, rest) = match ch {
+        '0' => (Token::Digit(true), rest),
+        '#' => (Token::Digit(false), rest),
+        '.' => (Token::Point, rest),
+        ',' => (Token::Comma, rest),
+        '%' => (Token::Percent, rest),
+        '‰' => (Token::Permille, rest),
+        ';' => (Token::Separator, rest),
+        '"' | '\'' => {
+            let end = rest.find(ch).ok_or(NumberPatternError::UnterminatedQuote)?;
+            (Token::Literal(&rest[..end]), &rest[end + 1..])
+        },
+        '\\' => {
+            let escaped = rest.chars().next().ok_or(NumberPatternError::UnterminatedEscape)?;
+            let len = escaped.len_utf8();
+            (Token::Literal(&rest[..len]), &rest[len..])
+        },
+        _ => (Token::Literal(&s[..ch.len_utf8()]), rest),
+    };
+    *s = rest;
+    Ok(Some(token))
+}
+
+/// One section of the pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Section<'a> {
+    text: &'a str,
+    /// Count of integer digit placeholders
+    int_digits: usize,
+    /// Minimum integer digits, from the leftmost `0`
+    min_int: usize,
+    /// Minimum fraction digits, to the rightmost `0`
+    min_fraction: usize,
+    /// Count of fraction digit placeholders
+    max_fraction: usize,
+    grouping: bool,
+    /// Power of ten multiplied to the value, by `%` `‰` and scaling commas
+    scale: i32,
+}
+
+impl<'a> Section<'a> {
+    fn parse(text: &'a str) -> Result<Self, NumberPatternError> {
+        let mut this = Self {
+            text,
+            int_digits: 0,
+            min_int: 0,
+            min_fraction: 0,
+            max_fraction: 0,
+            grouping: false,
+            scale: 0,
+        };
+        let mut s = text;
+        let mut in_fraction = false;
+        let mut seen_digit = false;
+        // commas after a digit placeholder, grouping if an integer digit follows, else scaling
+        let mut commas = 0;
+
+        while let Some(token) = next_token(&mut s)? {
+            match token {
+                Token::Digit(zero) if in_fraction => {
+                    this.max_fraction += 1;
+                    if zero {
+                        this.min_fraction = this.max_fraction;
+                    }
+                },
+                Token::Digit(zero) => {
+                    this.grouping |= commas != 0;
+                    commas = 0;
+                    this.int_digits += 1;
+                    if zero && this.min_int == 0 {
+                        this.min_int = 1;
+                    } else if this.min_int != 0 {
+                        this.min_int += 1;
+                    }
+                },
+                Token::Point => in_fraction = true,
+                Token::Comma if seen_digit => commas += 1,
+                Token::Percent => this.scale += 2,
+                Token::Permille => this.scale += 3,
+                Token::Comma | Token::Separator | Token::Literal(_) => (),
+            }
+            seen_digit |= matches!(token, Token::Digit(_));
+        }
+        if this.max_fraction > MAX_FRACTION {
+            return Err(NumberPatternError::TooManyDigits);
+        }
+        this.scale -= 3 * commas;
+        Ok(this)
+    }
+
+    /// Digits of the value in this section
+    fn digits(&self, x: f64) -> Digits {
+        let power = (0..self.scale.unsigned_abs()).fold(1.0, |power, _| power * 10.0);
+        let x = if self.scale < 0 { x / power } else { x * power };
+
+        let mut digits = Digits { buf: ArrayBuf::new(), int: 0 };
+        write!(digits.buf, "{x:.*}", self.max_fraction).unwrap();
+        let s = digits.buf.as_str();
+        digits.int = s.find('.').unwrap_or(s.len());
+        digits
+    }
+
+    fn write(&self, w: &mut dyn Write, digits: &Digits) -> fmt::Result {
+        let int = digits.int_str();
+        let fraction = digits.fraction_str();
+        let n = int.len();
+        let width = n.max(self.min_int);
+        let fraction_len = fraction.trim_end_matches('0').len().max(self.min_fraction);
+
+        let write_int = |w: &mut dyn Write, p: usize| {
+            w.write_char(if p <= n { char::from(int.as_bytes()[n - p]) } else { '0' })?;
+            if self.grouping && p > 1 && (p - 1).is_multiple_of(3) {
+                w.write_char(',')?;
+            }
+            Ok(())
+        };
+
+        let mut s = self.text;
+        let mut k = self.int_digits;
+        let mut j = 0;
+        let mut in_fraction = false;
+        let mut seen_digit = false;
+
+        while let Some(token) = next_token(&mut s).map_err(|_| fmt::Error)? {
+            match token {
+                Token::Digit(_) if in_fraction => {
+                    j += 1;
+                    if j <= fraction_len {
+                        w.write_char(char::from(fraction.as_bytes()[j - 1]))?;
+                    }
+                },
+                Token::Digit(_) => {
+                    // the leftmost placeholder writes the extra digits
+                    let top = if k == self.int_digits { width.max(k) } else { k };
+                    (k..=top).rev().filter(|&p| p <= width).try_for_each(|p| write_int(w, p))?;
+                    k -= 1;
+                },
+                Token::Point if !in_fraction => {
+                    in_fraction = true;
+                    if self.int_digits == 0 {
+                        (1..=width).rev().try_for_each(|p| write_int(w, p))?;
+                    }
+                    if fraction_len != 0 {
+                        w.write_char('.')?;
+                    }
+                },
+                Token::Point => w.write_char('.')?,
+                Token::Comma if !seen_digit => w.write_char(',')?,
+                Token::Comma | Token::Separator => (),
+                Token::Percent => w.write_char('%')?,
+                Token::Permille => w.write_char('‰')?,
+                Token::Literal(literal) => w.write_str(literal)?,
+            }
+            seen_digit |= matches!(token, Token::Digit(_));
+        }
+        Ok(())
+    }
+}
+
+/// Digits of the value, rounded to the fraction placeholders
+struct Digits {
+    buf: ArrayBuf<DIGITS>,
+    /// End of integer digits
+    int: usize,
+}
+
+impl Digits {
+    /// Integer digits, zero is empty
+    fn int_str(&self) -> &str {
+        let int = &self.buf.as_str()[..self.int];
+        if int == "0" { "" } else { int }
+    }
+
+    fn fraction_str(&self) -> &str {
+        self.buf.as_str().get(self.int + 1..).unwrap_or("")
+    }
+
+    fn is_zero(&self) -> bool {
+        self.int_str().is_empty() && self.fraction_str().bytes().all(|ch| ch == b'0')
+    }
+}
+
+/// Excel and .NET custom numeric format pattern, like `#,##0.00;(#,##0.00);"-"`
+///
+/// - Sections are separated by `;`, for positive, negative and zero values.
+///   Without the negative section, negative values are written by the first section with `-`,
+///   empty sections are like missing
+/// - `0` is a digit or zero, `#` is a digit if any
+/// - `.` is the decimal point, written if any fraction digit is written
+/// - `,` between digit placeholders groups the integer digits by 3,
+///   `,` after the last integer digit placeholder divides the value by 1000
+/// - `%` and `‰` multiply the value by 100 and 1000
+/// - `"text"` `'text'` `\c` and other chars are literals
+///
+/// Values are rounded half to even, NaN and infinities are written like core
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// let pattern = NumberPattern::parse(r#"#,##0.00;(#,##0.00);"-""#).unwrap();
+/// assert_eq!(pattern.display(1234.5).to_string(), "1,234.50");
+/// assert_eq!(pattern.display(-1234.5).to_string(), "(1,234.50)");
+/// assert_eq!(pattern.display(0.0).to_string(), "-");
+///
+/// let pattern = NumberPattern::parse("000.0%").unwrap();
+/// assert_eq!(pattern.display(0.1234).to_string(), "012.3%");
+///
+/// let pattern = NumberPattern::parse(r#"#,##0.0,," M""#).unwrap();
+/// assert_eq!(pattern.display(1234567890.0).to_string(), "1,234.6 M");
+///
+/// let pattern = NumberPattern::parse("(###) ###-####").unwrap();
+/// assert_eq!(pattern.display(5551234567.0).to_string(), "(555) 123-4567");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberPattern<'a> {
+    positive: Section<'a>,
+    negative: Option<Section<'a>>,
+    zero: Option<Section<'a>>,
+}
+
+impl<'a> NumberPattern<'a> {
+    pub fn parse(pattern: &'a str) -> Result<Self, NumberPatternError> {
+        let mut sections = [""; 3];
+        let mut count = 0;
+        let mut s = pattern;
+        let mut start = pattern;
+
+        loop {
+            let before = s;
+            match next_token(&mut s)? {
+                Some(Token::Separator) | None => {
+                    let section = &start[..start.len() - before.len()];
+                    *sections.get_mut(count).ok_or(NumberPatternError::TooManySections)? = section;
+                    count += 1;
+                    if before.is_empty() {
+                        break;
+                    }
+                    start = s;
+                },
+                Some(_) => (),
+            }
+        }
+
+        let section = |text: &'a str| -> Result<_, NumberPatternError> {
+            Ok(if text.is_empty() { None } else { Some(Section::parse(text)?) })
+        };
+        Ok(Self {
+            positive: Section::parse(sections[0])?,
+            negative: section(sections[1])?,
+            zero: section(sections[2])?,
+        })
+    }
+
+    /// The part of the first section supported by [`FormatterBuilder`],
+    /// fraction digits as precision and grouping
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::Display;
+    /// let b = NumberPattern::parse("#,##0.00").unwrap().builder();
+    ///
+    /// let mut writter = String::new();
+    /// b.with(&mut writter, |f| 1234.5.fmt(f)).unwrap();
+    /// assert_eq!(writter, "1,234.50");
+    /// ```
+    pub fn builder(&self) -> FormatterBuilder<'static> {
+        let mut b = FormatterBuilder::new();
+        b.precision(self.positive.max_fraction as u16);
+        if self.positive.grouping {
+            b.grouping(',', 3);
+        }
+        b
+    }
+
+    /// Format the value by the pattern
+    pub fn format<W: Write>(&self, mut writer: W, value: f64) -> fmt::Result {
+        if !value.is_finite() {
+            return write!(writer, "{value}");
+        }
+        let (mut section, mut minus) = match self.negative {
+            Some(negative) if value < 0.0 => (negative, false),
+            _ => (self.positive, value < 0.0),
+        };
+        let mut digits = section.digits(value.abs());
+
+        if digits.is_zero() {
+            minus = false;
+            if let Some(zero) = self.zero {
+                section = zero;
+                digits = zero.digits(0.0);
+            }
+        }
+        if minus {
+            writer.write_char('-')?;
+        }
+        section.write(&mut writer, &digits)
+    }
+
+    /// Display the value by the pattern, width, fill and align apply to the whole output
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::Display;
+    /// let pattern = NumberPattern::parse("0.0;[0.0]").unwrap();
+    /// assert_eq!(format!("{:>8}", pattern.display(-2.25)), "   [2.2]");
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().align(Alignment::Left).width(6).with(&mut writter, |f| {
+    ///     pattern.display(3.0).fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "3.0   ");
+    /// ```
+    pub fn display(&self, value: f64) -> impl Display + '_ {
+        PatternDisplay(self, value)
+    }
+}
+
+struct PatternDisplay<'a>(&'a NumberPattern<'a>, f64);
+
+impl Display for PatternDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut count = Count { inner: Sink, chars: 0 };
+        self.0.format(&mut count, self.1)?;
+
+        let pad = Pad::from_formatter(f, PadAlign::Right);
+        let (pre, post) = pad.split(count.chars);
+        pad.write_fill(f, pre)?;
+        self.0.format(&mut *f, self.1)?;
+        pad.write_fill(f, post)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(pattern: &str, x: f64) -> String {
+        NumberPattern::parse(pattern).unwrap().display(x).to_string()
+    }
+
+    #[test]
+    fn placeholders() {
+        let cases = [
+            ("0", 0.0, "0"),
+            ("#", 0.0, ""),
+            ("#.##", 0.5, ".5"),
+            ("#.##", 1.0, "1"),
+            ("0.0#", 1.0, "1.0"),
+            ("0.0#", 1.256, "1.26"),
+            ("00000", 123.0, "00123"),
+            ("#0", 12345.0, "12345"),
+            ("0.00", 2.675, "2.67"),
+            (".00", 12.5, "12.50"),
+            ("#,#", 1234567.0, "1,234,567"),
+            ("#,##0", 12.0, "12"),
+            ("#,##0", 0.0, "0"),
+            ("0,000", 12.0, "0,012"),
+            ("0,", 12345.0, "12"),
+            ("0.0%", 0.5, "50.0%"),
+            ("0‰", 0.5, "500‰"),
+            ("0.#", f64::NAN, "NaN"),
+            ("0", -f64::INFINITY, "-inf"),
+        ];
+        for (pattern, x, exp) in cases {
+            assert_eq!(run(pattern, x), exp, "{pattern} {x}");
+        }
+    }
+
+    #[test]
+    fn literals() {
+        assert_eq!(run(r##""$"#,##0.00"##, 1234.5), "$1,234.50");
+        assert_eq!(run(r"\#0", 7.0), "#7");
+        assert_eq!(run("'0.0' 0", 7.0), "0.0 7");
+        assert_eq!(run("0 kg", 7.0), "7 kg");
+        assert_eq!(run(r#"0" ; "0"#, 12.0), "1 ; 2");
+        assert_eq!(run(",0", 7.0), ",7");
+    }
+
+    #[test]
+    fn sections() {
+        let pattern = r#"#,##0.00;(#,##0.00);"-""#;
+        assert_eq!(run(pattern, -0.001), "-");
+        assert_eq!(run(pattern, -1.0), "(1.00)");
+        assert_eq!(run("0.0", -1.0), "-1.0");
+        assert_eq!(run("0.0", -0.01), "0.0");
+        assert_eq!(run("0.0;;zero", -1.0), "-1.0");
+        assert_eq!(run("0.0;;zero", 0.0), "zero");
+        assert_eq!(run("0;(0)", -0.2), "(0)");
+        assert_eq!(run("", 5.0), "");
+    }
+
+    #[test]
+    fn errors() {
+        use NumberPatternError::*;
+        assert_eq!(NumberPattern::parse("0;0;0;0"), Err(TooManySections));
+        assert_eq!(NumberPattern::parse(r#"0"abc"#), Err(UnterminatedQuote));
+        assert_eq!(NumberPattern::parse("0\\"), Err(UnterminatedEscape));
+        assert_eq!(NumberPattern::parse(&format!("0.{}", "0".repeat(101))), Err(TooManyDigits));
+        assert!(NumberPattern::parse(&format!("0.{}", "0".repeat(100))).is_ok());
+    }
+}
@@ -0,0 +1,679 @@
+//! Software post-processing of the closure output in [`FormatterBuilder::with`]
+//!
+//! The closure output is captured into a fixed size buffer,
+//! then measured and written out through [`Transform`] with padding.
+//! When the output is longer than the buffer, it is streamed,
+//! and only the padding after it is written
+
+use core::fmt::{self, Formatter, Write};
+
+use crate::{
+    case::CaseMap,
+    pretty::{AlignFields, PrettyIndent},
+    number::{Grouping, Pad, PadAlign},
+    Alignment, Fill, FormatTrait, FormatterBuilder,
+};
+
+/// Capacity of the capture buffer in bytes
+const CAPTURE: usize = 1024;
+
+/// Capacity of the digit run buffer, longer than the digits of [`f64::MAX`],
+/// a longer run is written out without grouping
+const RUN: usize = 320;
+
+/// Fixed capacity string buffer, error on overflow
+pub(crate) struct ArrayBuf<const N: usize> {
+    buf: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> ArrayBuf<N> {
+    pub(crate) const fn new() -> Self {
+        Self { buf: [0; N], len: 0 }
+    }
+
+    pub(crate) fn as_str(&self) -> &str {
+        // only complete utf-8 str is pushed
+        core::str::from_utf8(&self.buf[..self.len]).unwrap()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.len = 0;
+    }
+}
+
+impl<const N: usize> Write for ArrayBuf<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        self.buf.get_mut(self.len..end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Count written chars
+pub(crate) struct Count<W> {
+    pub inner: W,
+    pub chars: usize,
+}
+
+impl<W: Write> Write for Count<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.chars += s.chars().count();
+        self.inner.write_str(s)
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.chars += 1;
+        self.inner.write_char(c)
+    }
+}
+
+/// Word char, digits next to it are not a decimal number
+fn is_word(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '.'
+}
+
+/// Number symbols to rewrite into
+#[derive(Debug, Clone, Copy)]
+struct Symbols {
+    grouping: Option<Grouping>,
+    decimal: char,
+    minus: &'static str,
+    digits: Option<[char; 10]>,
+}
+
+impl Symbols {
+    fn new(b: &FormatterBuilder<'_>) -> Self {
+        let locale = b.locale;
+        let locale_grouping = locale.and_then(|locale| {
+            Some(Grouping { separator: locale.grouping_separator()?, size: locale.group_size()? })
+        });
+        Self {
+            grouping: b.grouping.or(locale_grouping),
+            decimal: locale.map_or('.', |locale| locale.decimal_separator()),
+            minus: locale.map_or("-", |locale| locale.minus_sign()),
+            digits: locale.and_then(|locale| locale.digits()),
+        }
+    }
+
+    fn digit(&self, digit: char) -> char {
+        self.digits.map_or(digit, |digits| digits[digit as usize - '0' as usize])
+    }
+}
+
+/// Position in the output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Text,
+    /// `-` before a number maybe
+    Minus,
+    /// Integer digits of a number
+    Int,
+    /// `.` after integer digits, decimal point maybe
+    Point,
+    Fraction,
+    /// `e` or `E` after a number, exponent maybe
+    Exp(char),
+    ExpDigits,
+}
+
+/// Streaming transforms of the builder, e.g case, digit grouping and locale symbols
+///
+/// Only the number at the start of the output is rewritten, after an optional sign or head,
+/// it is a digit run not followed by a word char, optional fraction and exponent
+pub(crate) struct Transform<W> {
+    pub inner: W,
+    case: Option<CaseMap>,
+    symbols: Symbols,
+    state: State,
+    /// Leading zeros of current number, counted and not buffered
+    zeros: usize,
+    /// Integer digits of current number after the leading zeros
+    run: ArrayBuf<RUN>,
+    /// The run is longer than the buffer, the digits are written through
+    long: bool,
+    /// The leading number may still start, it is the only number rewritten
+    body: bool,
+    /// Writing the head before the leading number, see [`Transform::write_head`]
+    head: bool,
+}
+
+impl<W: Write> Transform<W> {
+    pub(crate) fn new(b: &FormatterBuilder<'_>, inner: W) -> Self {
+        Self {
+            inner,
+            case: b.case.map(CaseMap::new),
+            symbols: Symbols::new(b),
+            state: State::Text,
+            zeros: 0,
+            run: ArrayBuf::new(),
+            long: false,
+            body: true,
+            head: false,
+        }
+    }
+
+    /// Push an integer digit of current number
+    fn push_digit(&mut self, ch: char) -> fmt::Result {
+        if self.long {
+            return self.inner.write_char(self.symbols.digit(ch));
+        }
+        if ch == '0' && self.run.len == 0 {
+            self.zeros += 1;
+            return Ok(());
+        }
+        if self.run.write_char(ch).is_err() {
+            self.long = true;
+            self.end_run(true)?;
+            return self.inner.write_char(self.symbols.digit(ch));
+        }
+        Ok(())
+    }
+
+    /// End of the integer digits, rewrite it when it is a number
+    fn end_run(&mut self, number: bool) -> fmt::Result {
+        let zeros = (0..self.zeros).map(|_| b'0');
+        let run = &self.run.buf[..self.run.len];
+        let len = self.zeros + run.len();
+
+        for (i, digit) in zeros.chain(run.iter().copied()).enumerate() {
+            match self.symbols.grouping {
+                Some(grouping) if number && !self.long && i != 0 && grouping.is_boundary(len - i) => {
+                    self.inner.write_char(grouping.separator)?;
+                },
+                _ => (),
+            }
+            self.inner.write_char(if number { self.symbols.digit(digit.into()) } else { digit.into() })?;
+        }
+
+        self.zeros = 0;
+        self.run.clear();
+        self.body = false;
+        Ok(())
+    }
+
+    /// Write the head before the digits, e.g a currency symbol,
+    /// the number after it is rewritten whatever chars the head has
+    pub(crate) fn write_head(&mut self, head: &str) -> fmt::Result {
+        self.head = true;
+        let result = self.write_str(head);
+        self.head = false;
+        result
+    }
+
+    fn write_text(&mut self, ch: char) -> fmt::Result {
+        self.state = State::Text;
+        // a sign may be before the leading number
+        self.body &= self.head || matches!(ch, '+' | ' ');
+        self.inner.write_char(ch)
+    }
+
+    pub(crate) fn finish(&mut self) -> fmt::Result {
+        if let Some(mut case) = self.case.take() {
+            let result = case.finish(|ch| self.map_char(ch));
+            self.case = Some(case);
+            result?;
+        }
+        match self.state {
+            State::Minus => self.inner.write_char('-')?,
+            State::Int => self.end_run(true)?,
+            State::Point => {
+                self.end_run(true)?;
+                self.inner.write_char('.')?;
+            },
+            State::Exp(e) => self.inner.write_char(e)?,
+            State::Text | State::Fraction | State::ExpDigits => (),
+        }
+        self.state = State::Text;
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Transform<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().try_for_each(|ch| self.write_char(ch))
+    }
+
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        match self.case.take() {
+            Some(mut case) => {
+                let result = case.push(ch, |ch| self.map_char(ch));
+                self.case = Some(case);
+                result
+            },
+            None => self.map_char(ch),
+        }
+    }
+}
+
+impl<W: Write> Transform<W> {
+    /// Rewrite the char after the case mapping
+    fn map_char(&mut self, ch: char) -> fmt::Result {
+        let digit = ch.is_ascii_digit();
+
+        match self.state {
+            State::Text if self.body && digit => {
+                self.state = State::Int;
+                self.long = false;
+                self.push_digit(ch)
+            },
+            State::Text if self.body && ch == '-' => {
+                self.state = State::Minus;
+                Ok(())
+            },
+            State::Text => self.write_text(ch),
+            State::Minus if digit => {
+                self.inner.write_str(self.symbols.minus)?;
+                self.state = State::Int;
+                self.long = false;
+                self.push_digit(ch)
+            },
+            State::Minus => {
+                self.write_text('-')?;
+                self.map_char(ch)
+            },
+            State::Int if digit => self.push_digit(ch),
+            State::Int if ch == '.' => {
+                self.state = State::Point;
+                Ok(())
+            },
+            State::Int if ch == 'e' || ch == 'E' => {
+                self.end_run(true)?;
+                self.state = State::Exp(ch);
+                Ok(())
+            },
+            State::Int => {
+                self.end_run(!is_word(ch))?;
+                self.write_text(ch)
+            },
+            State::Point if digit => {
+                self.end_run(true)?;
+                self.inner.write_char(self.symbols.decimal)?;
+                self.state = State::Fraction;
+                self.inner.write_char(self.symbols.digit(ch))
+            },
+            State::Point => {
+                self.end_run(true)?;
+                self.write_text('.')?;
+                self.map_char(ch)
+            },
+            State::Fraction | State::ExpDigits if digit => {
+                self.inner.write_char(self.symbols.digit(ch))
+            },
+            State::Fraction if ch == 'e' || ch == 'E' => {
+                self.state = State::Exp(ch);
+                Ok(())
+            },
+            State::Fraction | State::ExpDigits => self.write_text(ch),
+            State::Exp(e) if digit || ch == '-' || ch == '+' => {
+                self.inner.write_char(e)?;
+                self.state = State::ExpDigits;
+                match ch {
+                    '-' => self.inner.write_str(self.symbols.minus),
+                    '+' => self.inner.write_char('+'),
+                    _ => self.inner.write_char(self.symbols.digit(ch)),
+                }
+            },
+            State::Exp(e) => {
+                self.write_text(e)?;
+                self.map_char(ch)
+            },
+        }
+    }
+}
+
+/// Length of sign and radix prefix, zeros of sign-aware zero pad are inserted after it
+fn sign_prefix_len(s: &str) -> usize {
+    let sign = s.starts_with(['+', '-', ' ']) as usize;
+    let rest = &s[sign..];
+    let prefix = ["0x", "0o", "0b"].iter()
+        .find(|prefix| rest.strip_prefix(**prefix)
+            .is_some_and(|digits| digits.starts_with(|ch: char| ch.is_ascii_hexdigit())))
+        .map_or(0, |prefix| prefix.len());
+    sign + prefix
+}
+
+/// Capture the closure output, and write it out with padding
+struct Capture<'a, W> {
+    builder: &'a FormatterBuilder<'a>,
+    /// Output is a number, right-aligned by default
+    numeric: bool,
+    /// Length of the head, zeros of sign-aware zero pad are inserted after it
+    head: fn(&str) -> usize,
+    /// Head is before the digits, e.g a currency symbol, see [`Transform::write_head`]
+    digits_head: bool,
+    buf: ArrayBuf<CAPTURE>,
+    /// Output is too long, written out through the transform
+    stream: Option<Transform<Count<W>>>,
+    writer: Option<W>,
+}
+
+impl<W: Write> Capture<'_, W> {
+    fn pad(&self) -> Pad {
+        let b = self.builder;
+        let width = b.width.unwrap_or(0).into();
+        if b.sign_aware_zero_pad {
+            return Pad { fill: '0', align: PadAlign::AfterSign, width };
+        }
+        let fill = b.fill_align.and_then(|(fill, _)| fill).map_or(' ', Fill::as_char);
+        let align = match b.fill_align {
+            Some((_, Alignment::Left)) => PadAlign::Left,
+            Some((_, Alignment::Right)) => PadAlign::Right,
+            Some((_, Alignment::Center)) => PadAlign::Center,
+            None => {
+                // like core, numbers are right-aligned, others are left-aligned
+                let s = self.buf.as_str();
+                let s = s.strip_prefix(['+', '-', ' ']).unwrap_or(s);
+                if self.numeric || s.starts_with(|ch: char| ch.is_ascii_digit()) {
+                    PadAlign::Right
+                } else {
+                    PadAlign::Left
+                }
+            },
+        };
+        Pad { fill, align, width }
+    }
+
+    /// Zeros of integer precision, [`FormatterBuilder::int_precision`]
+    fn int_zeros(&self, tail: &str) -> usize {
+        match self.builder.precision {
+            Some(precision) if self.builder.int_precision
+                && !tail.is_empty()
+                && tail.chars().all(|ch| match self.builder.int_format {
+                    Some(FormatTrait::LowerHex | FormatTrait::UpperHex) => ch.is_ascii_hexdigit(),
+                    _ => ch.is_ascii_digit(),
+                }) =>
+            {
+                usize::from(precision).saturating_sub(tail.len())
+            },
+            _ => 0,
+        }
+    }
+
+    /// Write `head`, zeros and `tail` through the transform
+    fn write_parts<T: Write>(&self, writer: T, head: &str, zeros: usize, tail: &str)
+        -> Result<T, fmt::Error>
+    {
+        let mut t = Transform::new(self.builder, writer);
+        match self.digits_head {
+            true => t.write_head(head)?,
+            false => t.write_str(head)?,
+        }
+        (0..zeros).try_for_each(|_| t.write_char('0'))?;
+        t.write_str(tail)?;
+        t.finish()?;
+        Ok(t.inner)
+    }
+
+    fn finish(mut self) -> fmt::Result {
+        if let Some(mut stream) = self.stream.take() {
+            stream.finish()?;
+            let pad = self.pad();
+            if pad.align == PadAlign::Left {
+                let padding = pad.width.saturating_sub(stream.inner.chars);
+                pad.write_fill(&mut stream.inner.inner, padding)?;
+            }
+            return Ok(());
+        }
+
+        let pad = self.pad();
+        let raw = self.buf.as_str();
+        let (head, tail) = raw.split_at((self.head)(raw));
+        let mut zeros = self.int_zeros(tail);
+        let len = self.write_parts(Count { inner: Sink, chars: 0 }, head, zeros, tail)?.chars;
+        let mut writer = self.writer.take().unwrap();
+        let padding = pad.width.saturating_sub(len);
+
+        if pad.align == PadAlign::AfterSign {
+            let run = &tail[..tail.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(tail.len())];
+            let digits = zeros + run.len();
+            zeros += match Symbols::new(self.builder).grouping {
+                // a run longer than the buffer is not grouped
+                Some(grouping) if digits != 0
+                    && run.trim_start_matches('0').len() <= RUN
+                    && (self.digits_head || !head.ends_with(is_word)) =>
+                {
+                    let others = len - digits - grouping.separators(digits);
+                    grouping.zero_fill(digits, pad.width.saturating_sub(others)) - digits
+                },
+                _ => padding,
+            };
+            self.write_parts(&mut writer, head, zeros, tail)?;
+            return Ok(());
+        }
+
+        let (pre, post) = pad.split(len);
+        pad.write_fill(&mut writer, pre)?;
+        self.write_parts(&mut writer, head, zeros, tail)?;
+        pad.write_fill(&mut writer, post)
+    }
+
+    /// Switch to streaming, the padding before the output is unknown and not written
+    fn overflow(&mut self, s: &str) -> fmt::Result {
+        let writer = self.writer.take().unwrap();
+        let mut stream = Transform::new(self.builder, Count { inner: writer, chars: 0 });
+        let raw = self.buf.as_str();
+        let (head, tail) = raw.split_at((self.head)(raw));
+        match self.digits_head {
+            true => stream.write_head(head)?,
+            false => stream.write_str(head)?,
+        }
+        stream.write_str(tail)?;
+        stream.write_str(s)?;
+        self.stream = Some(stream);
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Capture<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if let Some(stream) = &mut self.stream {
+            return stream.write_str(s);
+        }
+        if self.buf.write_str(s).is_err() {
+            return self.overflow(s);
+        }
+        Ok(())
+    }
+}
+
+pub(crate) struct Sink;
+
+impl Write for Sink {
+    fn write_str(&mut self, _: &str) -> fmt::Result {
+        Ok(())
+    }
+}
+
+/// [`FormatterBuilder::with`] with post-processing
+///
+/// The closure is formatted without width, fill and align,
+/// the padding is applied to the whole output after post-processing
+pub(crate) fn with<W, F>(b: &FormatterBuilder<'_>, writer: W, f: F) -> fmt::Result
+where
+    W: Write,
+    F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+{
+    run(b, writer, false, sign_prefix_len, false, f)
+}
+
+/// Like [`with`], but the output is a number, e.g `NaN` `inf` are right-aligned by default
+pub(crate) fn with_numeric<W, F>(b: &FormatterBuilder<'_>, writer: W, f: F) -> fmt::Result
+where
+    W: Write,
+    F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+{
+    run(b, writer, true, sign_prefix_len, false, f)
+}
+
+/// Like [`with_numeric`], but the digits start after `head`,
+/// zeros of sign-aware zero pad are inserted there
+pub(crate) fn with_head<W, F>(b: &FormatterBuilder<'_>, writer: W, head: fn(&str) -> usize, f: F) -> fmt::Result
+where
+    W: Write,
+    F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+{
+    run(b, writer, true, head, true, f)
+}
+
+fn run<W, F>(
+    b: &FormatterBuilder<'_>,
+    writer: W,
+    numeric: bool,
+    head: fn(&str) -> usize,
+    digits_head: bool,
+    f: F,
+) -> fmt::Result
+where
+    W: Write,
+    F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+{
+    let inner = FormatterBuilder {
+        sign: b.sign,
+        alternate: b.alternate,
+        precision: b.precision,
+        ..FormatterBuilder::new()
+    };
+    let mut align = AlignFields::new(b.indent_writer(writer), b.align_style());
+    let mut capture = Capture {
+        builder: b,
+        numeric,
+        head,
+        digits_head,
+        buf: ArrayBuf::new(),
+        stream: None,
+        writer: Some(&mut align),
+    };
+    inner.with_plain(PrettyIndent::new(&mut capture, b.pretty_style()), f)?;
+    capture.finish()?;
+    align.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Case, Locale, Sign};
+    use std::fmt::Display;
+
+    fn run(b: &FormatterBuilder<'_>, n: &dyn Display) -> String {
+        let mut out = String::new();
+        b.with(&mut out, |f| n.fmt(f)).unwrap();
+        out
+    }
+
+    #[test]
+    fn capture_overflow() {
+        let mut b = FormatterBuilder::new();
+        b.grouping(',', 3);
+        let long = "x".repeat(CAPTURE * 2);
+        assert_eq!(run(&b, &format_args!("1234 {long}")), format!("1,234 {long}"));
+        assert_eq!(run(b.width(5), &format_args!("1234 {long}")), format!("1,234 {long}"));
+        b.width(CAPTURE as u16 * 3).align(Alignment::Left);
+        let out = run(&b, &format_args!("1234 {long}"));
+        assert_eq!(out, format!("1,234 {long}{}", " ".repeat(CAPTURE - 6)));
+
+        // padding before the output is unknown
+        b.align(Alignment::Right);
+        assert_eq!(run(&b, &format_args!("1234 {long}")), format!("1,234 {long}"));
+        b.align(Alignment::Center);
+        assert_eq!(run(&b, &format_args!("1234 {long}")), format!("1,234 {long}"));
+
+        let mut b = FormatterBuilder::new();
+        b.grouping(',', 3).width(600).precision(0);
+        let out = run(&b, &f64::MAX);
+        assert_eq!(out.len(), 600);
+        assert!(out.trim_start().starts_with("179,769,313"));
+    }
+
+    #[test]
+    fn digit_runs() {
+        let mut b = FormatterBuilder::new();
+        b.grouping(',', 3);
+        assert_eq!(run(&b, &"1234567"), "1,234,567");
+        assert_eq!(run(&b, &"-1234567.1234"), "-1,234,567.1234");
+        assert_eq!(run(&b, &"+1234 1234"), "+1,234 1234");
+        assert_eq!(run(&b, &" 1234"), " 1,234");
+        for s in ["a1234", "1234b", "0x1234", "1234_5", "(12345)", "Order 12345 at 2024-01-15 10:30"] {
+            assert_eq!(run(&b, &s), s);
+        }
+        assert_eq!(run(&b, &"1.2345e12345"), "1.2345e12345");
+        for (s, exp) in [("12345.", "12,345."), ("12345.x", "12,345.x"), ("1234e", "1,234e"), ("1234-5", "1,234-5")] {
+            assert_eq!(run(&b, &s), exp);
+        }
+    }
+
+    #[test]
+    fn long_run() {
+        let long = "7".repeat(RUN + 80);
+        let mut b = FormatterBuilder::new();
+        assert_eq!(run(b.case(Case::Upper), &long), long);
+        assert_eq!(run(b.grouping(',', 3), &long), long);
+        assert_eq!(run(&b, &format_args!("-{long}.5 1234")), format!("-{long}.5 1234"));
+        assert_eq!(run(b.locale(Locale::FA_IR).grouping(None, 3), &"1".repeat(RUN + 1)), "۱".repeat(RUN + 1));
+
+        // leading zeros are not buffered
+        let mut b = FormatterBuilder::new();
+        b.grouping(',', 3).sign_aware_zero_pad(true).width(499);
+        let out = run(&b, &5);
+        assert_eq!(out.len(), 499);
+        assert!(out.starts_with("000,000,") && out.ends_with(",005"));
+        assert_eq!(run(&b, &long), format!("{}{long}", "0".repeat(99)));
+    }
+
+    #[test]
+    fn int_precision() {
+        let mut b = FormatterBuilder::new();
+        b.int_precision(true).precision(5);
+        assert_eq!(run(&b, &42), "00042");
+        assert_eq!(run(&b, &-42), "-00042");
+        assert_eq!(run(&b, &1234567), "1234567");
+        assert_eq!(run(b.precision(2000), &5).len(), 2000);
+        b.precision(5);
+        assert_eq!(run(&b, &1.5), "1.50000");
+        assert_eq!(run(&b, &"text"), "text");
+        assert_eq!(run(&b, &"cafe"), "cafe");
+        assert_eq!(run(b.int_format(FormatTrait::Octal), &"bad"), "bad");
+        assert_eq!(run(b.int_format(FormatTrait::LowerHex), &"bad"), "00bad");
+        b.int_format(None);
+        assert_eq!(run(b.sign(Sign::Plus).width(8), &42), "  +00042");
+        assert_eq!(run(b.sign_aware_zero_pad(true), &42), "+0000042");
+        assert_eq!(run(b.grouping(',', 3).width(None), &42), "+00,042");
+
+        let mut out = String::new();
+        b.grouping(None, 3).sign(None).alternate(true).width(9).int_format(FormatTrait::LowerHex);
+        b.with(&mut out, |f| fmt::LowerHex::fmt(&0x1f, f)).unwrap();
+        assert_eq!(out, "0x000001f");
+    }
+
+    #[test]
+    fn locale() {
+        let mut b = FormatterBuilder::new();
+        b.locale(Locale::DE_DE);
+        assert_eq!(run(&b, &"-1234567.25"), "-1.234.567,25");
+        assert_eq!(run(&b, &"1.5e-7 v1.2 0x1234 -inf 1-2"), "1,5e-7 v1.2 0x1234 -inf 1-2");
+        assert_eq!(run(b.grouping('_', 3), &"1234.5"), "1_234,5");
+
+        b.grouping(None, 3).locale(Locale::SV_SE);
+        assert_eq!(run(&b, &"-1234.5e-3"), "\u{2212}1\u{a0}234,5e\u{2212}3");
+
+        b.locale(Locale::HI_IN);
+        assert_eq!(run(&b, &"-1234567.5"), "-१२,३४,५६७.५");
+
+        b.locale(Locale::FA_IR).sign_aware_zero_pad(true).width(7);
+        assert_eq!(run(&b, &-12), "\u{200e}\u{2212}۰٬۰۱۲");
+    }
+
+    #[test]
+    fn zero_pad() {
+        let mut b = FormatterBuilder::new();
+        b.grouping(',', 3).sign_aware_zero_pad(true);
+        for (width, exp) in [(5, "1,234"), (6, "01,234"), (8, "0,001,234"), (10, "00,001,234")] {
+            assert_eq!(run(b.width(width), &1234), exp);
+        }
+        assert_eq!(run(b.width(10).sign(Sign::Plus), &-1234.5), "-001,234.5");
+        assert_eq!(run(b.width(10).sign(Sign::Space), &1234), " 0,001,234");
+        assert_eq!(run(b.width(8).alternate(true), &format_args!("{:#x}", 0x1234)), "0x001234");
+        assert_eq!(run(b.width(6).sign(None), &f64::NAN), "000NaN");
+    }
+}
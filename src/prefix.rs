@@ -0,0 +1,202 @@
+//! SI and binary prefix adapters, like `1.5 KiB` `3.20 MHz` `12.0 µs`
+
+use core::fmt::{self, Display, Formatter, Write};
+
+use crate::{
+    number::{Pad, PadAlign},
+    post::{Count, Sink},
+    FormatterBuilder,
+};
+
+const SI: &[&str] = &[
+    "q", "r", "y", "z", "a", "f", "p", "n", "µ", "m",
+    "",
+    "k", "M", "G", "T", "P", "E", "Z", "Y", "R", "Q",
+];
+
+const BINARY: &[&str] = &["", "Ki", "Mi", "Gi", "Ti", "Pi", "Ei", "Zi", "Yi"];
+
+/// Format value with SI prefix and unit, like `1.5 kB` `12.0 µs`
+///
+/// The mantissa is formatted through a [`FormatterBuilder`] with sign, precision
+/// and sign aware zero pad, width, fill and align apply to the whole quantity
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// use std::fmt::Display;
+/// assert_eq!(format!("{}", Si(1500.0, "B")), "1.5 kB");
+/// assert_eq!(format!("{:+.2}", Si(3.2e6, "Hz")), "+3.20 MHz");
+/// assert_eq!(format!("{:*>9.1}", Si(0.0000123, "s")), "**12.3 µs");
+///
+/// let mut writter = String::new();
+/// FormatterBuilder::new().width(8).precision(1).with(&mut writter, |f| {
+///     Si(999.96, "m").fmt(f)
+/// }).unwrap();
+/// assert_eq!(writter, "  1.0 km");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Si<'a>(pub f64, pub &'a str);
+
+/// Format value with binary (IEC) prefix and unit, like `1.5 KiB`
+///
+/// Values less than 1024 have no prefix, others like [`Si`]
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// assert_eq!(format!("{}", Binary(1536.0, "B")), "1.5 KiB");
+/// assert_eq!(format!("{:.2}", Binary(5e9, "B")), "4.66 GiB");
+/// assert_eq!(format!("{:<8}", Binary(12.0, "B")), "12 B    ");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Binary<'a>(pub f64, pub &'a str);
+
+impl Display for Si<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Prefixed { value: self.0, unit: self.1, base: 1000.0, prefixes: SI, none: 10 }.fmt(f)
+    }
+}
+
+impl Display for Binary<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Prefixed { value: self.0, unit: self.1, base: 1024.0, prefixes: BINARY, none: 0 }.fmt(f)
+    }
+}
+
+struct Prefixed<'a> {
+    value: f64,
+    unit: &'a str,
+    base: f64,
+    prefixes: &'static [&'static str],
+    /// Index of the empty prefix
+    none: usize,
+}
+
+impl Prefixed<'_> {
+    /// Value divided by `base^exp`
+    ///
+    /// `base^exp` is exact for powers of 1024 and up to `1000^7`,
+    /// larger decimal powers like `1000^8` of `Y` are rounded
+    fn scaled(&self, index: usize) -> f64 {
+        let exp = index as isize - self.none as isize;
+        let power = (0..exp.unsigned_abs()).fold(1.0, |power, _| power * self.base);
+
+        if exp < 0 {
+            self.value * power
+        } else {
+            self.value / power
+        }
+    }
+
+    /// Index of the prefix, the mantissa is in `1..base` after rounding if possible
+    fn prefix(&self, precision: Option<usize>) -> usize {
+        let abs = self.value.abs();
+        let mut index = self.none;
+
+        if abs == 0.0 || !abs.is_finite() {
+            return index;
+        }
+        while index + 1 < self.prefixes.len() && self.scaled(index).abs() >= self.base {
+            index += 1;
+        }
+        while index > 0 && self.scaled(index).abs() < 1.0 {
+            index -= 1;
+        }
+
+        if let Some(precision) = precision
+            && index + 1 < self.prefixes.len()
+        {
+            let mut int = IntPart(0.0, false);
+            write!(int, "{:.*}", precision, self.scaled(index).abs()).ok();
+            if int.0 >= self.base {
+                index += 1;
+            }
+        }
+        index
+    }
+}
+
+impl Display for Prefixed<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let index = self.prefix(f.precision());
+        let mantissa = self.scaled(index);
+        let prefix = self.prefixes[index];
+        let space = if prefix.is_empty() && self.unit.is_empty() { "" } else { " " };
+        let suffix = space.len() + prefix.chars().count() + self.unit.chars().count();
+        let pad = Pad::from_formatter(f, PadAlign::Right);
+
+        let mut builder = FormatterBuilder::from_formatter_lossy(f);
+        builder.fill_align = None;
+        builder.width = None;
+        if pad.align == PadAlign::AfterSign {
+            builder.width(u16::try_from(pad.width.saturating_sub(suffix)).unwrap_or(u16::MAX));
+        }
+
+        let mut count = Count { inner: Sink, chars: 0 };
+        builder.with(&mut count, |f| mantissa.fmt(f))?;
+
+        let (pre, post) = pad.split(count.chars + suffix);
+
+        pad.write_fill(f, pre)?;
+        builder.with(&mut *f, |f| mantissa.fmt(f))?;
+        f.write_str(space)?;
+        f.write_str(prefix)?;
+        f.write_str(self.unit)?;
+        pad.write_fill(f, post)
+    }
+}
+
+/// Integer part of a formatted unsigned number
+struct IntPart(f64, bool);
+
+impl Write for IntPart {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for ch in s.chars() {
+            match ch.to_digit(10) {
+                Some(digit) if !self.1 => self.0 = self.0 * 10.0 + f64::from(digit),
+                _ => self.1 = true,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn si() {
+        let cases = [
+            (0.0, "0 s"), (1.0, "1 s"), (999.0, "999 s"), (1000.0, "1 ks"),
+            (-2.5e-3, "-2.5 ms"), (1e-6, "1 µs"), (1e31, "10 Qs"), (1e-31, "0.1 qs"),
+            (f64::INFINITY, "inf s"), (f64::NAN, "NaN s"),
+        ];
+        for (value, exp) in cases {
+            assert_eq!(format!("{}", Si(value, "s")), exp);
+        }
+        assert_eq!(format!("{}", Si(1500.0, "")), "1.5 k");
+        assert_eq!(format!("{}", Si(15.0, "")), "15");
+    }
+
+    #[test]
+    fn rounding() {
+        assert_eq!(format!("{:.1}", Si(999.96, "B")), "1.0 kB");
+        assert_eq!(format!("{:.2}", Si(999.96, "B")), "999.96 B");
+        assert_eq!(format!("{:.0}", Si(0.9996, "V")), "1 V");
+        assert_eq!(format!("{:.1}", Binary(1023.96, "B")), "1.0 KiB");
+        assert_eq!(format!("{:.1}", Binary(1023.0, "B")), "1023.0 B");
+    }
+
+    #[test]
+    fn padding() {
+        assert_eq!(format!("{:>10.1}", Binary(1536.0, "B")), "   1.5 KiB");
+        assert_eq!(format!("{:^12.1}", Binary(1536.0, "B")), "  1.5 KiB   ");
+        assert_eq!(format!("{:010.1}", Binary(-1536.0, "B")), "-001.5 KiB");
+        assert_eq!(format!("{:+09.1}", Si(1500.0, "B")), "+001.5 kB");
+        assert_eq!(format!("{:3}", Si(1500.0, "B")), "1.5 kB");
+    }
+}
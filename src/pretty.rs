@@ -0,0 +1,442 @@
+//! Layout of pretty [`Debug`](core::fmt::Debug) output
+//!
+//! Core writes the indentation of `{:#?}` as a standalone `"    "` at the line start,
+//! so it is rewritten while streaming
+
+use core::fmt::{self, Write};
+
+use crate::{indent::write_spaces, FormatterBuilder};
+
+/// Indentation unit of `{:#?}`, used by [`FormatterBuilder::pretty_indent`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    /// Spaces of each level
+    Spaces(u8),
+    /// A tab of each level
+    Tab,
+}
+
+impl FormatterBuilder<'_> {
+    /// Rewrite the indentation of `{:#?}` output, when [`alternate`](FormatterBuilder::alternate)
+    ///
+    /// Only the indentation written by core debug builders is rewritten, like
+    /// [`debug_struct`](core::fmt::Formatter::debug_struct) and [`debug_list`](core::fmt::Formatter::debug_list)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::Debug;
+    /// #[derive(Debug)]
+    /// struct Point { x: i32, y: [i32; 1] }
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().alternate(true).pretty_indent(IndentStyle::Spaces(2)).with(&mut writter, |f| {
+    ///     Point { x: 1, y: [2] }.fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "Point {\n  x: 1,\n  y: [\n    2,\n  ],\n}");
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().alternate(true).pretty_indent(IndentStyle::Tab).with(&mut writter, |f| {
+    ///     Some(1).fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "Some(\n\t1,\n)");
+    /// ```
+    pub fn pretty_indent(&mut self, pretty_indent: impl Into<Option<IndentStyle>>) -> &mut Self {
+        self.pretty_indent = pretty_indent.into();
+        self
+    }
+
+    /// Align the values of struct fields in `{:#?}` output, when [`alternate`](FormatterBuilder::alternate)
+    ///
+    /// Like gofmt, a section of consecutive single-line fields is aligned after the colon,
+    /// and numeric values in the section are right-aligned.
+    /// A multi-line field ends the section
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// use std::fmt::Debug;
+    /// #[derive(Debug)]
+    /// struct Server { name: &'static str, port: u16, timeout: f32, tags: [u8; 1] }
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().alternate(true).align_fields(true).with(&mut writter, |f| {
+    ///     Server { name: "app", port: 80, timeout: 2.5, tags: [1] }.fmt(f)
+    /// }).unwrap();
+    /// assert_eq!(writter, "\
+    /// Server {
+    ///     name:    \"app\",
+    ///     port:     80,
+    ///     timeout: 2.5,
+    ///     tags: [
+    ///         1,
+    ///     ],
+    /// }");
+    /// ```
+    pub fn align_fields(&mut self, align_fields: bool) -> &mut Self {
+        self.align_fields = align_fields;
+        self
+    }
+
+    /// Indent style when alternate
+    pub(crate) fn pretty_style(&self) -> Option<IndentStyle> {
+        self.pretty_indent.filter(|_| self.alternate)
+    }
+
+    /// [`align_fields`](FormatterBuilder::align_fields) when alternate
+    pub(crate) fn align_style(&self) -> bool {
+        self.align_fields && self.alternate
+    }
+}
+
+/// Core's indentation of each level
+const CORE_INDENT: &str = "    ";
+
+/// Rewrite the indentation of core debug builders, [`None`] writes through
+pub(crate) struct PrettyIndent<W> {
+    inner: W,
+    style: Option<IndentStyle>,
+    line_start: bool,
+}
+
+impl<W: Write> PrettyIndent<W> {
+    pub(crate) fn new(inner: W, style: Option<IndentStyle>) -> Self {
+        Self { inner, style, line_start: true }
+    }
+}
+
+impl<W: Write> Write for PrettyIndent<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        match self.style {
+            Some(style) if self.line_start && s == CORE_INDENT => match style {
+                IndentStyle::Spaces(n) => write_spaces(&mut self.inner, n.into()),
+                IndentStyle::Tab => self.inner.write_char('\t'),
+            },
+            _ => {
+                if !s.is_empty() {
+                    self.line_start = s.ends_with('\n');
+                }
+                self.inner.write_str(s)
+            },
+        }
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.line_start = c == '\n';
+        self.inner.write_char(c)
+    }
+}
+
+/// Capacity of the buffered section in bytes
+const SECTION: usize = 1024;
+
+/// Capacity of the buffered section in lines
+const SECTION_LINES: usize = 32;
+
+/// Single-line field in the section
+#[derive(Debug, Clone, Copy, Default)]
+struct Field {
+    /// Byte range of the line, without the newline
+    start: usize,
+    end: usize,
+    /// Bytes of the indentation
+    indent: usize,
+    /// Bytes of the name
+    name: usize,
+    /// Chars of the name
+    name_chars: usize,
+    /// Chars of the numeric value, [`None`] when not numeric
+    number: Option<usize>,
+}
+
+impl Field {
+    /// Parse a line like `    name: value,`
+    fn parse(line: &str, start: usize) -> Option<Self> {
+        let content = line.trim_start_matches([' ', '\t']);
+        let indent = line.len() - content.len();
+        let ident = content.strip_prefix("r#").unwrap_or(content);
+        let name_end = ident.find(|ch: char| !ch.is_alphanumeric() && ch != '_')?;
+        let name = content.len() - ident.len() + name_end;
+        let value = content[name..].strip_prefix(": ")?.strip_suffix(',')?;
+        if name_end == 0 || value.is_empty() {
+            return None;
+        }
+
+        let digits = value.strip_prefix('-').unwrap_or(value);
+        let number = (digits.starts_with(|ch: char| ch.is_ascii_digit())
+            && digits.chars().all(|ch| ch.is_ascii_digit() || matches!(ch, '.' | '_' | ',' | 'e' | 'E' | '+' | '-')))
+            .then(|| value.chars().count());
+
+        Some(Self {
+            start,
+            end: start + line.len(),
+            indent,
+            name,
+            name_chars: content[..name].chars().count(),
+            number,
+        })
+    }
+}
+
+/// Only complete utf-8 str is pushed, and split at ascii chars
+fn as_str(buf: &[u8]) -> &str {
+    core::str::from_utf8(buf).unwrap()
+}
+
+/// Align the sections of single-line fields, disabled writes through
+pub(crate) struct AlignFields<W> {
+    inner: W,
+    enabled: bool,
+    buf: [u8; SECTION],
+    len: usize,
+    fields: [Field; SECTION_LINES],
+    count: usize,
+    /// Start of the current line in the buffer
+    line: usize,
+    /// Current line is too long, written through
+    through: bool,
+}
+
+impl<W: Write> AlignFields<W> {
+    pub(crate) fn new(inner: W, enabled: bool) -> Self {
+        Self {
+            inner,
+            enabled,
+            buf: [0; SECTION],
+            len: 0,
+            fields: [Field::default(); SECTION_LINES],
+            count: 0,
+            line: 0,
+            through: false,
+        }
+    }
+
+    /// Write the section aligned, and remove it from the buffer
+    fn flush_section(&mut self) -> fmt::Result {
+        let fields = &self.fields[..self.count];
+        let name_width = fields.iter().map(|field| field.name_chars).max().unwrap_or(0);
+        let number_width = fields.iter().filter_map(|field| field.number).max().unwrap_or(0);
+
+        for field in fields {
+            let name_end = field.start + field.indent + field.name;
+            let mut spaces = name_width - field.name_chars + 1;
+            if let Some(number) = field.number {
+                spaces += number_width - number;
+            }
+            self.inner.write_str(as_str(&self.buf[field.start..name_end]))?;
+            self.inner.write_char(':')?;
+            write_spaces(&mut self.inner, spaces)?;
+            self.inner.write_str(as_str(&self.buf[name_end + 2..field.end]))?;
+            self.inner.write_char('\n')?;
+        }
+        self.count = 0;
+
+        self.buf.copy_within(self.line..self.len, 0);
+        self.len -= self.line;
+        self.line = 0;
+        Ok(())
+    }
+
+    /// The current line is complete
+    fn end_line(&mut self) -> fmt::Result {
+        let line = as_str(&self.buf[self.line..self.len - 1]);
+        match Field::parse(line, self.line) {
+            Some(field) => {
+                let first = self.fields[0];
+                let same_indent = self.count == 0
+                    || as_str(&self.buf[first.start..first.start + first.indent]) == &line[..field.indent];
+                if !same_indent || self.count == SECTION_LINES {
+                    self.flush_section()?;
+                    return self.end_line();
+                }
+                self.fields[self.count] = field;
+                self.count += 1;
+                self.line = self.len;
+            },
+            None => {
+                self.flush_section()?;
+                self.inner.write_str(as_str(&self.buf[0..self.len]))?;
+                self.len = 0;
+            },
+        }
+        Ok(())
+    }
+
+    pub(crate) fn finish(&mut self) -> fmt::Result {
+        self.flush_section()?;
+        self.inner.write_str(as_str(&self.buf[0..self.len]))?;
+        self.len = 0;
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for AlignFields<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if !self.enabled {
+            return self.inner.write_str(s);
+        }
+        for piece in s.split_inclusive('\n') {
+            let end_line = piece.ends_with('\n');
+            if self.through {
+                self.through = !end_line;
+                self.inner.write_str(piece)?;
+                continue;
+            }
+
+            let end = self.len + piece.len();
+            if end > SECTION {
+                self.finish()?;
+                self.through = !end_line;
+                self.inner.write_str(piece)?;
+                continue;
+            }
+            self.buf[self.len..end].copy_from_slice(piece.as_bytes());
+            self.len = end;
+            if end_line {
+                self.end_line()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::BTreeMap, fmt::Debug};
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Config {
+        name: &'static str,
+        ports: Vec<u16>,
+        env: BTreeMap<&'static str, (i32, Option<char>)>,
+        text: &'static str,
+    }
+
+    fn config() -> Config {
+        Config {
+            name: "app",
+            ports: vec![80, 443],
+            env: BTreeMap::from([("a", (1, None)), ("b", (2, Some('x')))]),
+            text: "    \n    ",
+        }
+    }
+
+    fn run(b: &FormatterBuilder<'_>, value: &dyn Debug) -> String {
+        let mut out = String::new();
+        b.with(&mut out, |f| value.fmt(f)).unwrap();
+        out
+    }
+
+    #[test]
+    fn pretty_indent() {
+        let core = format!("{:#?}", config());
+        let mut b = FormatterBuilder::new();
+        b.alternate(true);
+
+        let styles = [
+            (IndentStyle::Spaces(2), "  "),
+            (IndentStyle::Spaces(0), ""),
+            (IndentStyle::Spaces(20), "                    "),
+            (IndentStyle::Tab, "\t"),
+        ];
+        for (style, unit) in styles {
+            let exp: String = core.lines()
+                .map(|line| {
+                    let content = line.trim_start_matches(' ');
+                    let level = (line.len() - content.len()) / 4;
+                    format!("{}{content}\n", unit.repeat(level))
+                })
+                .collect();
+            assert_eq!(run(b.pretty_indent(style), &config()) + "\n", exp);
+        }
+        assert_eq!(run(b.pretty_indent(None), &config()), core);
+        assert_eq!(run(b.alternate(false).pretty_indent(IndentStyle::Tab), &config()), format!("{:?}", config()));
+    }
+
+    #[test]
+    fn with_options() {
+        let mut b = FormatterBuilder::new();
+        b.alternate(true).pretty_indent(IndentStyle::Spaces(1)).width(3);
+        assert_eq!(run(&b, &[1, 2]), "[\n   1,\n   2,\n]");
+
+        // only the leading number is grouped
+        b.grouping(',', 3).indent(2);
+        assert_eq!(run(&b, &[1234]), "  [\n   1234,\n  ]");
+        assert_eq!(run(&b, &1234), "  1,234");
+    }
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Server {
+        name: &'static str,
+        port: u16,
+        r#type: char,
+        timeout: f64,
+        inner: Inner,
+        id: i64,
+        limit: u32,
+    }
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    struct Inner {
+        a: i8,
+        bb: i32,
+        text: &'static str,
+    }
+
+    fn server() -> Server {
+        Server {
+            name: "app",
+            port: 8080,
+            r#type: 'x',
+            timeout: -1.5,
+            inner: Inner { a: 1, bb: -10000, text: "a: b," },
+            id: -3,
+            limit: 100000,
+        }
+    }
+
+    #[test]
+    fn align_fields() {
+        let mut b = FormatterBuilder::new();
+        b.alternate(true).align_fields(true);
+        assert_eq!(run(&b, &server()), "\
+Server {
+    name:    \"app\",
+    port:    8080,
+    type:    'x',
+    timeout: -1.5,
+    inner: Inner {
+        a:         1,
+        bb:   -10000,
+        text: \"a: b,\",
+    },
+    id:        -3,
+    limit: 100000,
+}");
+        assert_eq!(run(&b, &config()), format!("{:#?}", config()));
+        assert_eq!(run(b.alternate(false), &server()), format!("{:?}", server()));
+
+        let long = [Inner { a: 1, bb: 2, text: "" }, Inner { a: 3, bb: 4, text: "x".repeat(2000).leak() }];
+        let exp = format!("{long:#?}").replace("a: 1", "a:    1").replace("bb: 2", "bb:   2")
+            .replacen("a: 3", "a:  3", 1);
+        assert_eq!(run(b.alternate(true), &long), exp);
+    }
+
+    #[test]
+    fn align_with_options() {
+        let mut b = FormatterBuilder::new();
+        b.alternate(true).align_fields(true).pretty_indent(IndentStyle::Tab).indent_with("// ").grouping(',', 3);
+        assert_eq!(run(&b, &Inner { a: 1, bb: 1234, text: "" }), "\
+// Inner {
+// \ta:       1,
+// \tbb:   1234,
+// \ttext: \"\",
+// }");
+    }
+}
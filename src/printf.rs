@@ -0,0 +1,539 @@
+use core::fmt::{self, Formatter, Write};
+
+use crate::{Alignment, FormatterBuilder, Sign, SpecError};
+
+/// The formatting trait of a format spec, e.g `x` of `{:x}`
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FormatTrait {
+    /// [`fmt::Display`], like `{}`
+    Display,
+    /// [`fmt::Debug`], like `{:?}`
+    Debug,
+    /// [`fmt::LowerHex`], like `{:x}`
+    LowerHex,
+    /// [`fmt::UpperHex`], like `{:X}`
+    UpperHex,
+    /// [`fmt::Octal`], like `{:o}`
+    Octal,
+    /// [`fmt::Binary`], like `{:b}`
+    Binary,
+    /// [`fmt::LowerExp`], like `{:e}`
+    LowerExp,
+    /// [`fmt::UpperExp`], like `{:E}`
+    UpperExp,
+}
+
+/// Error of [`FormatterBuilder::from_printf`] and [`printf`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PrintfError {
+    /// Spec not starts with `%`
+    MissingPercent,
+    /// Spec ends before the conversion char
+    UnexpectedEnd,
+    /// Not a printf conversion char
+    InvalidConversion(char),
+    /// printf conversion that can't be mapped exactly, e.g `%g` `%a` `%n`
+    UnsupportedConversion(char),
+    /// printf flag that can't be mapped exactly with the conversion, e.g `%#o` `%#d`
+    UnsupportedFlag {
+        flag: char,
+        conversion: char,
+    },
+    /// Extra chars after the conversion char
+    TrailingCharacters,
+    /// `*` width or precision, only available in [`printf`]
+    StarArgument,
+    /// Argument of the index is missing
+    MissingArgument(usize),
+    /// Argument of the index can't be formatted by the conversion
+    ArgumentType {
+        index: usize,
+        conversion: char,
+    },
+    /// Invalid builder spec, e.g width overflow
+    Spec(SpecError),
+    /// Error from the writer or formatting trait
+    Fmt(fmt::Error),
+}
+
+impl fmt::Display for PrintfError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::MissingPercent => write!(f, "printf spec must starts with '%'"),
+            Self::UnexpectedEnd => write!(f, "unexpected end of printf spec"),
+            Self::InvalidConversion(ch) => write!(f, "invalid printf conversion {ch:?}"),
+            Self::UnsupportedConversion(ch) => {
+                write!(f, "printf conversion {ch:?} can't be mapped exactly")
+            },
+            Self::UnsupportedFlag { flag, conversion } => {
+                write!(f, "printf flag {flag:?} can't be mapped exactly with conversion {conversion:?}")
+            },
+            Self::TrailingCharacters => write!(f, "trailing characters after printf conversion"),
+            Self::StarArgument => write!(f, "'*' width or precision requires an argument"),
+            Self::MissingArgument(index) => write!(f, "missing printf argument {index}"),
+            Self::ArgumentType { index, conversion } => {
+                write!(f, "printf argument {index} can't be formatted by conversion {conversion:?}")
+            },
+            Self::Spec(err) => err.fmt(f),
+            Self::Fmt(err) => err.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for PrintfError {}
+
+impl From<SpecError> for PrintfError {
+    fn from(value: SpecError) -> Self {
+        Self::Spec(value)
+    }
+}
+
+impl From<fmt::Error> for PrintfError {
+    fn from(value: fmt::Error) -> Self {
+        Self::Fmt(value)
+    }
+}
+
+/// Argument of [`printf`]
+pub trait PrintfArg {
+    /// Is the formatting trait implemented
+    fn supports(&self, format_trait: FormatTrait) -> bool;
+
+    /// Can be formatted by the printf conversion char, like `d` `f` `s`
+    ///
+    /// Default is [`supports`](PrintfArg::supports) of the formatting trait of the conversion
+    fn supports_conversion(&self, conversion: char) -> bool {
+        self.supports(conversion_trait(conversion))
+    }
+
+    /// Format by the formatting trait, only called when [`supports_conversion`](PrintfArg::supports_conversion)
+    fn fmt_as(&self, format_trait: FormatTrait, f: &mut Formatter<'_>) -> fmt::Result;
+
+    /// Value of `*` width or precision
+    fn as_count(&self) -> Option<isize> {
+        None
+    }
+}
+
+macro_rules! impl_printf_arg {
+    (@int $($ty:ty),+ $(,)?) => {$(
+        impl PrintfArg for $ty {
+            fn supports(&self, _: FormatTrait) -> bool {
+                true
+            }
+
+            fn supports_conversion(&self, conversion: char) -> bool {
+                match conversion {
+                    'u' => !i128::try_from(*self).is_ok_and(|n| n < 0),
+                    _ => is_int_conversion(conversion),
+                }
+            }
+
+            fn fmt_as(&self, format_trait: FormatTrait, f: &mut Formatter<'_>) -> fmt::Result {
+                match format_trait {
+                    FormatTrait::Display => fmt::Display::fmt(self, f),
+                    FormatTrait::Debug => fmt::Debug::fmt(self, f),
+                    FormatTrait::LowerHex => fmt::LowerHex::fmt(self, f),
+                    FormatTrait::UpperHex => fmt::UpperHex::fmt(self, f),
+                    FormatTrait::Octal => fmt::Octal::fmt(self, f),
+                    FormatTrait::Binary => fmt::Binary::fmt(self, f),
+                    FormatTrait::LowerExp => fmt::LowerExp::fmt(self, f),
+                    FormatTrait::UpperExp => fmt::UpperExp::fmt(self, f),
+                }
+            }
+
+            fn as_count(&self) -> Option<isize> {
+                (*self).try_into().ok()
+            }
+        }
+    )+};
+    (@float $($ty:ty),+ $(,)?) => {$(
+        impl PrintfArg for $ty {
+            fn supports(&self, format_trait: FormatTrait) -> bool {
+                matches!(format_trait, FormatTrait::Display
+                    | FormatTrait::Debug
+                    | FormatTrait::LowerExp
+                    | FormatTrait::UpperExp)
+            }
+
+            fn supports_conversion(&self, conversion: char) -> bool {
+                matches!(conversion, 'f' | 'F')
+            }
+
+            fn fmt_as(&self, format_trait: FormatTrait, f: &mut Formatter<'_>) -> fmt::Result {
+                match format_trait {
+                    FormatTrait::LowerExp => fmt::LowerExp::fmt(self, f),
+                    FormatTrait::UpperExp => fmt::UpperExp::fmt(self, f),
+                    FormatTrait::Debug => fmt::Debug::fmt(self, f),
+                    _ => fmt::Display::fmt(self, f),
+                }
+            }
+        }
+    )+};
+    (@text $($ty:ty => $conversion:pat),+ $(,)?) => {$(
+        impl PrintfArg for $ty {
+            fn supports(&self, format_trait: FormatTrait) -> bool {
+                matches!(format_trait, FormatTrait::Display | FormatTrait::Debug)
+            }
+
+            fn supports_conversion(&self, conversion: char) -> bool {
+                matches!(conversion, $conversion)
+            }
+
+            fn fmt_as(&self, format_trait: FormatTrait, f: &mut Formatter<'_>) -> fmt::Result {
+                match format_trait {
+                    FormatTrait::Debug => fmt::Debug::fmt(self, f),
+                    _ => fmt::Display::fmt(self, f),
+                }
+            }
+        }
+    )+};
+}
+impl_printf_arg!(@int i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+impl_printf_arg!(@float f32, f64);
+impl_printf_arg!(@text &str => 's', char => 'c' | 's', bool => 's');
+
+fn is_int_conversion(conversion: char) -> bool {
+    matches!(conversion, 'd' | 'i' | 'u' | 'x' | 'X' | 'o' | 'b')
+}
+
+/// Formatting trait of the printf conversion char
+fn conversion_trait(conversion: char) -> FormatTrait {
+    match conversion {
+        'x' => FormatTrait::LowerHex,
+        'X' => FormatTrait::UpperHex,
+        'o' => FormatTrait::Octal,
+        'b' => FormatTrait::Binary,
+        _ => FormatTrait::Display,
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Count {
+    Is(usize),
+    Star,
+}
+
+/// Parsed printf conversion, `*` not yet resolved
+#[derive(Debug, Clone, Copy)]
+struct Conversion {
+    left: bool,
+    sign: Option<Sign>,
+    alternate: bool,
+    zero: bool,
+    width: Option<Count>,
+    precision: Option<Count>,
+    conversion: char,
+}
+
+fn parse_count(s: &str) -> (Option<Count>, &str) {
+    if let Some(rest) = s.strip_prefix('*') {
+        return (Some(Count::Star), rest);
+    }
+    let end = s.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(s.len());
+    if end == 0 {
+        return (None, s);
+    }
+    // saturating, width or precision overflow is reported by SpecError
+    let n = s[..end].bytes().fold(0usize, |n, b| {
+        n.saturating_mul(10).saturating_add((b - b'0').into())
+    });
+    (Some(Count::Is(n)), &s[end..])
+}
+
+impl Conversion {
+    /// Parse after `%`, returns the rest string
+    fn parse(mut s: &str) -> Result<(Self, &str), PrintfError> {
+        let mut conv = Self {
+            left: false,
+            sign: None,
+            alternate: false,
+            zero: false,
+            width: None,
+            precision: None,
+            conversion: '\0',
+        };
+
+        loop {
+            match s.chars().next() {
+                Some('-') => conv.left = true,
+                Some('+') => conv.sign = Some(Sign::Plus),
+                Some(' ') => { conv.sign.get_or_insert(Sign::Space); },
+                Some('#') => conv.alternate = true,
+                Some('0') => conv.zero = true,
+                _ => break,
+            }
+            s = &s[1..];
+        }
+
+        (conv.width, s) = parse_count(s);
+
+        if let Some(rest) = s.strip_prefix('.') {
+            let (precision, rest) = parse_count(rest);
+            conv.precision = Some(precision.unwrap_or(Count::Is(0)));
+            s = rest;
+        }
+
+        s = s.trim_start_matches(['h', 'l', 'L', 'q', 'j', 'z', 't']);
+
+        let mut chars = s.chars();
+        conv.conversion = chars.next().ok_or(PrintfError::UnexpectedEnd)?;
+        conv.check()?;
+
+        Ok((conv, chars.as_str()))
+    }
+
+    fn unsupported_flag(&self, flag: char) -> PrintfError {
+        PrintfError::UnsupportedFlag { flag, conversion: self.conversion }
+    }
+
+    fn check(&self) -> Result<(), PrintfError> {
+        match self.conversion {
+            'o' | 'X' | 'f' | 'F' | 'c' | 's' | 'd' | 'i' | 'u' if self.alternate => {
+                Err(self.unsupported_flag('#'))
+            },
+            'd' | 'i' | 'u' | 'x' | 'X' | 'o' | 'b' | 'f' | 'F' | 'c' | 's' => Ok(()),
+            ch @ ('e' | 'E' | 'g' | 'G' | 'a' | 'A' | 'n' | 'p') => {
+                Err(PrintfError::UnsupportedConversion(ch))
+            },
+            ch => Err(PrintfError::InvalidConversion(ch)),
+        }
+    }
+
+    fn format_trait(&self) -> FormatTrait {
+        conversion_trait(self.conversion)
+    }
+
+    /// Build with resolved `*` width and precision
+    fn build(
+        &self,
+        width: Option<isize>,
+        precision: Option<isize>,
+    ) -> Result<FormatterBuilder<'static>, PrintfError> {
+        let mut b = FormatterBuilder::new();
+        // negative `*` width is the `-` flag
+        let left = self.left || width.is_some_and(|width| width < 0);
+        let width = width.map(isize::unsigned_abs);
+        // negative `*` precision is taken as if omitted
+        let precision = match precision.and_then(|precision| usize::try_from(precision).ok()) {
+            None if matches!(self.conversion, 'f' | 'F') => Some(6),
+            precision => precision,
+        };
+
+        // sign flags are ignored by text and unsigned conversions, like C
+        let text = matches!(self.conversion, 's' | 'c');
+        let unsigned = matches!(self.conversion, 'u' | 'x' | 'X' | 'o' | 'b');
+        b.sign(self.sign.filter(|_| !text && !unsigned))
+            .alternate(self.alternate)
+            .try_width(width)?
+            .try_precision(precision)?;
+
+        // integer precision is the minimum digits, and `0` flag is ignored
+        let int = is_int_conversion(self.conversion);
+        let int_precision = int && precision.is_some();
+        b.int_precision(int_precision).int_format(Some(self.format_trait()).filter(|_| int));
+
+        if left {
+            b.align(Alignment::Left);
+        } else if self.zero && !int_precision && !text {
+            b.sign_aware_zero_pad(true);
+        } else {
+            b.align(Alignment::Right);
+        }
+
+        Ok(b)
+    }
+}
+
+fn count_value(count: Option<Count>, star: impl FnOnce() -> Result<isize, PrintfError>)
+    -> Result<Option<isize>, PrintfError>
+{
+    Ok(match count {
+        Some(Count::Is(n)) => Some(n.try_into().unwrap_or(isize::MAX)),
+        Some(Count::Star) => Some(star()?),
+        None => None,
+    })
+}
+
+impl FormatterBuilder<'_> {
+    /// Build from a single printf conversion spec, like `%-+08.3f` `%#x` `%5s`
+    ///
+    /// - Length modifiers (`l` `ll` `z` etc) are ignored
+    /// - `f` and `F` use precision 6 by default, like printf
+    /// - `+` and space flags are ignored by `s` `c` and unsigned conversions `u` `x` `X` `o` `b`
+    /// - `%#x` format zero as `0x0`, and `NaN` `inf` use the Rust spelling
+    /// - `*` width or precision can only be used by [`printf`]
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let (b, t) = FormatterBuilder::from_printf("%-+08.3f").unwrap();
+    /// assert_eq!(t, FormatTrait::Display);
+    ///
+    /// let mut output = String::new();
+    /// b.with(&mut output, |f| std::fmt::Display::fmt(&3.14159, f)).unwrap();
+    /// assert_eq!(output, "+3.142  ");
+    ///
+    /// assert_eq!(FormatterBuilder::from_printf("%#x").unwrap().1, FormatTrait::LowerHex);
+    /// assert_eq!(FormatterBuilder::from_printf("%g").unwrap_err(), PrintfError::UnsupportedConversion('g'));
+    /// ```
+    pub fn from_printf(spec: &str) -> Result<(FormatterBuilder<'static>, FormatTrait), PrintfError> {
+        let spec = spec.strip_prefix('%').ok_or(PrintfError::MissingPercent)?;
+        let (conv, rest) = Conversion::parse(spec)?;
+
+        if !rest.is_empty() {
+            return Err(PrintfError::TrailingCharacters);
+        }
+
+        let width = count_value(conv.width, || Err(PrintfError::StarArgument))?;
+        let precision = count_value(conv.precision, || Err(PrintfError::StarArgument))?;
+
+        Ok((conv.build(width, precision)?, conv.format_trait()))
+    }
+}
+
+/// Render a printf format string, like C `sprintf`
+///
+/// Conversions are translated by [`FormatterBuilder::from_printf`],
+/// `*` width and precision consumes an integer argument
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// let mut output = String::new();
+/// printf(&mut output, "%5s|%-4d|%#x|%.*f%%", &[&"ab", &42, &255u8, &2, &99.5]).unwrap();
+/// assert_eq!(output, "   ab|42  |0xff|99.50%");
+/// ```
+pub fn printf<W: Write>(
+    mut writer: W,
+    format: &str,
+    args: &[&dyn PrintfArg],
+) -> Result<(), PrintfError> {
+    let mut next_index = 0;
+    let mut next_arg = || {
+        let index = next_index;
+        next_index += 1;
+        args.get(index)
+            .map(|&arg| (index, arg))
+            .ok_or(PrintfError::MissingArgument(index))
+    };
+    let mut rest = format;
+
+    while let Some(i) = rest.find('%') {
+        writer.write_str(&rest[..i])?;
+        rest = &rest[i+1..];
+
+        if let Some(after) = rest.strip_prefix('%') {
+            writer.write_char('%')?;
+            rest = after;
+            continue;
+        }
+
+        let (conv, after) = Conversion::parse(rest)?;
+        rest = after;
+
+        let count = |(index, arg): (usize, &dyn PrintfArg)| {
+            arg.as_count().ok_or(PrintfError::ArgumentType { index, conversion: '*' })
+        };
+        let width = count_value(conv.width, || count(next_arg()?))?;
+        let precision = count_value(conv.precision, || count(next_arg()?))?;
+
+        let (index, arg) = next_arg()?;
+        let format_trait = conv.format_trait();
+
+        if !arg.supports_conversion(conv.conversion) {
+            return Err(PrintfError::ArgumentType { index, conversion: conv.conversion });
+        }
+
+        conv.build(width, precision)?
+            .with(&mut writer, |f| arg.fmt_as(format_trait, f))?;
+    }
+
+    writer.write_str(rest)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(format: &str, args: &[&dyn PrintfArg]) -> Result<String, PrintfError> {
+        let mut out = String::new();
+        printf(&mut out, format, args)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(run("%d %i %u", &[&-1, &2, &3u8]).unwrap(), "-1 2 3");
+        assert_eq!(run("%x %X %o %b", &[&255, &255, &8, &5]).unwrap(), "ff FF 10 101");
+        assert_eq!(run("%f %.2F", &[&1.5, &2.0]).unwrap(), "1.500000 2.00");
+        assert_eq!(run("%c%s", &[&'a', &"bc"]).unwrap(), "abc");
+        assert_eq!(run("%ld %llu %zu", &[&1i64, &2u64, &3usize]).unwrap(), "1 2 3");
+    }
+
+    #[test]
+    fn flags() {
+        assert_eq!(run("[%5d]", &[&42]).unwrap(), "[   42]");
+        assert_eq!(run("[%-5d]", &[&42]).unwrap(), "[42   ]");
+        assert_eq!(run("[%05d]", &[&-42]).unwrap(), "[-0042]");
+        assert_eq!(run("[%-05d]", &[&42]).unwrap(), "[42   ]");
+        assert_eq!(run("[%+d]", &[&42]).unwrap(), "[+42]");
+        assert_eq!(run("[% d]", &[&42]).unwrap(), "[ 42]");
+        assert_eq!(run("[%+ d]", &[&42]).unwrap(), "[+42]");
+        assert_eq!(run("[% +d]", &[&42]).unwrap(), "[+42]");
+        assert_eq!(run("[%#010x]", &[&255]).unwrap(), "[0x000000ff]");
+        assert_eq!(run("[%.2s]", &[&"abc"]).unwrap(), "[ab]");
+        assert_eq!(run("[%.5d]", &[&42]).unwrap(), "[00042]");
+        assert_eq!(run("[%+8.5d]", &[&42]).unwrap(), "[  +00042]");
+        assert_eq!(run("[%08.3x]", &[&255]).unwrap(), "[     0ff]");
+        assert_eq!(run("[%#.4x]", &[&15]).unwrap(), "[0x000f]");
+        assert_eq!(run("%.400d", &[&-5]).unwrap(), format!("-{}5", "0".repeat(399)));
+        assert_eq!(run("%.400x", &[&255]).unwrap(), format!("{}ff", "0".repeat(398)));
+        assert_eq!(run("[% s|%+3s|% c]", &[&"+", &"a", &'+']).unwrap(), "[+|  a|+]");
+        assert_eq!(run("%+x|% x|%+u|%+o", &[&255, &255, &3u8, &8]).unwrap(), "ff|ff|3|10");
+        assert_eq!(run("[%+05X|% #b]", &[&255, &5]).unwrap(), "[000FF|0b101]");
+    }
+
+    #[test]
+    fn star() {
+        assert_eq!(run("[%*d]", &[&4, &1]).unwrap(), "[   1]");
+        assert_eq!(run("[%*d]", &[&-4, &1]).unwrap(), "[1   ]");
+        assert_eq!(run("[%.*f]", &[&1, &1.25]).unwrap(), "[1.2]");
+        assert_eq!(run("[%.*f]", &[&-1, &1.25]).unwrap(), "[1.250000]");
+        assert_eq!(run("[%*.*f]", &[&6, &1, &1.25]).unwrap(), "[   1.2]");
+        assert_eq!(FormatterBuilder::from_printf("%*d").unwrap_err(), PrintfError::StarArgument);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(run("%d", &[]), Err(PrintfError::MissingArgument(0)));
+        assert_eq!(run("%d %d", &[&1]), Err(PrintfError::MissingArgument(1)));
+        assert_eq!(run("%x", &[&1.0]), Err(PrintfError::ArgumentType { index: 0, conversion: 'x' }));
+        assert_eq!(run("%*d", &[&"a", &1]), Err(PrintfError::ArgumentType { index: 0, conversion: '*' }));
+        assert_eq!(run("%d", &[&"abc"]), Err(PrintfError::ArgumentType { index: 0, conversion: 'd' }));
+        assert_eq!(run("%x", &[&'a']), Err(PrintfError::ArgumentType { index: 0, conversion: 'x' }));
+        assert_eq!(run("%u", &[&-1]), Err(PrintfError::ArgumentType { index: 0, conversion: 'u' }));
+        assert_eq!(run("%u", &[&u128::MAX]).unwrap(), u128::MAX.to_string());
+        assert_eq!(run("%f", &[&42]), Err(PrintfError::ArgumentType { index: 0, conversion: 'f' }));
+        assert_eq!(run("%F", &[&"1.5"]), Err(PrintfError::ArgumentType { index: 0, conversion: 'F' }));
+        assert_eq!(run("%s", &[&1]), Err(PrintfError::ArgumentType { index: 0, conversion: 's' }));
+        assert_eq!(run("%s", &[&1.5]), Err(PrintfError::ArgumentType { index: 0, conversion: 's' }));
+        assert_eq!(run("%c", &[&65]), Err(PrintfError::ArgumentType { index: 0, conversion: 'c' }));
+        assert_eq!(run("%c", &[&"a"]), Err(PrintfError::ArgumentType { index: 0, conversion: 'c' }));
+        assert_eq!(run("%s %s", &[&'a', &true]).unwrap(), "a true");
+        assert_eq!(run("%", &[]), Err(PrintfError::UnexpectedEnd));
+        assert_eq!(run("%k", &[]), Err(PrintfError::InvalidConversion('k')));
+        assert_eq!(run("%e", &[&1.0]), Err(PrintfError::UnsupportedConversion('e')));
+        assert_eq!(run("%#o", &[&1]), Err(PrintfError::UnsupportedFlag { flag: '#', conversion: 'o' }));
+        assert_eq!(FormatterBuilder::from_printf("d").unwrap_err(), PrintfError::MissingPercent);
+        assert_eq!(FormatterBuilder::from_printf("%dd").unwrap_err(), PrintfError::TrailingCharacters);
+        assert_eq!(
+            FormatterBuilder::from_printf("%70000d").unwrap_err(),
+            PrintfError::Spec(SpecError::WidthOverflow(70000)),
+        );
+    }
+}
@@ -0,0 +1,697 @@
+use core::fmt::{self, Formatter, Write};
+
+use crate::{
+    float::{exponent, ExpStyle, FloatWriter},
+    number::{GroupSize, Grouping, Measure, Pad, PadAlign},
+    Alignment, Fill, FormatterBuilder, RoundingMode, Sign, SpecError,
+};
+
+/// Value formatted by [`PySpec`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PyValue<'a> {
+    /// Python `int`
+    Int(i128),
+    /// Python `int`, out of [`i128`] range
+    UInt(u128),
+    /// Python `float`
+    Float(f64),
+    /// Python `str`
+    Str(&'a str),
+    /// Python `str` of one character
+    Char(char),
+    /// Python `bool`
+    Bool(bool),
+}
+
+/// Argument of [`PySpec::format`]
+pub trait PyArg {
+    fn py_value(&self) -> PyValue<'_>;
+}
+
+macro_rules! impl_py_arg {
+    ($variant:ident($($ty:ty),+ $(,)?)) => {$(
+        impl PyArg for $ty {
+            fn py_value(&self) -> PyValue<'_> {
+                PyValue::$variant((*self).into())
+            }
+        }
+    )+};
+}
+impl_py_arg!(Int(i8, i16, i32, i64, i128, u8, u16, u32, u64));
+impl_py_arg!(UInt(u128));
+impl_py_arg!(Float(f32, f64));
+impl_py_arg!(Str(&str));
+impl_py_arg!(Char(char));
+impl_py_arg!(Bool(bool));
+
+impl PyArg for isize {
+    fn py_value(&self) -> PyValue<'_> {
+        PyValue::Int(*self as i128)
+    }
+}
+
+impl PyArg for usize {
+    fn py_value(&self) -> PyValue<'_> {
+        PyValue::UInt(*self as u128)
+    }
+}
+
+impl PyArg for PyValue<'_> {
+    fn py_value(&self) -> PyValue<'_> {
+        *self
+    }
+}
+
+/// Error of [`PySpec`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PySpecError {
+    /// Unexpected char in spec
+    UnexpectedChar(char),
+    /// Width or precision is too large
+    Overflow,
+    /// Presentation type can't format the value, e.g `d` for `float`
+    UnknownType {
+        ty: char,
+        value: &'static str,
+    },
+    /// Option is not allowed for the value, e.g precision for `int`
+    NotAllowed(&'static str),
+    /// `c` for an integer out of unicode range
+    CharOverflow,
+    /// Error from the writer
+    Fmt(fmt::Error),
+}
+
+impl fmt::Display for PySpecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            Self::UnexpectedChar(ch) => write!(f, "invalid format specifier char {ch:?}"),
+            Self::Overflow => write!(f, "too many decimal digits in format string"),
+            Self::UnknownType { ty, value } => {
+                write!(f, "unknown format code {ty:?} for object of type '{value}'")
+            },
+            Self::NotAllowed(msg) => f.write_str(msg),
+            Self::CharOverflow => write!(f, "%c arg not in range(0x110000)"),
+            Self::Fmt(err) => err.fmt(f),
+        }
+    }
+}
+
+impl core::error::Error for PySpecError {}
+
+impl From<fmt::Error> for PySpecError {
+    fn from(value: fmt::Error) -> Self {
+        Self::Fmt(value)
+    }
+}
+
+/// Python format spec mini-language, like `,.2f` `_x` `=+10` `.3%`
+///
+/// `[[fill]align][sign]["z"]["#"]["0"][width][grouping]["." precision][type]`
+///
+/// The digits are formatted by [`FormatterBuilder`],
+/// then the padding, grouping, percentage and exponent are applied in software
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// let spec = PySpec::parse("*^+12,.2f").unwrap();
+/// assert_eq!(spec.display(&1234.5).to_string(), "*+1,234.50**");
+///
+/// let spec = PySpec::parse(".3%").unwrap();
+/// assert_eq!(spec.display(&0.25).to_string(), "25.000%");
+///
+/// let spec = PySpec::parse("#010_x").unwrap();
+/// assert_eq!(spec.display(&48879).to_string(), "0x000_beef");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PySpec {
+    fill: Option<char>,
+    align: Option<PadAlign>,
+    sign: Option<Sign>,
+    coerce_zero: bool,
+    alternate: bool,
+    zero: bool,
+    width: usize,
+    grouping: Option<char>,
+    precision: Option<u16>,
+    ty: Option<char>,
+    empty: bool,
+}
+
+fn parse_align(ch: char) -> Option<PadAlign> {
+    Some(match ch {
+        '<' => PadAlign::Left,
+        '>' => PadAlign::Right,
+        '^' => PadAlign::Center,
+        '=' => PadAlign::AfterSign,
+        _ => return None,
+    })
+}
+
+fn parse_usize(s: &str) -> Result<(Option<usize>, &str), PySpecError> {
+    let end = s.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(s.len());
+    if end == 0 {
+        return Ok((None, s));
+    }
+    let n = s[..end].parse().map_err(|_| PySpecError::Overflow)?;
+    Ok((Some(n), &s[end..]))
+}
+
+impl PySpec {
+    /// Parse a python format spec, without the `{:` `}`
+    pub fn parse(spec: &str) -> Result<Self, PySpecError> {
+        let mut this = Self {
+            fill: None,
+            align: None,
+            sign: None,
+            coerce_zero: false,
+            alternate: false,
+            zero: false,
+            width: 0,
+            grouping: None,
+            precision: None,
+            ty: None,
+            empty: spec.is_empty(),
+        };
+        let mut s = spec;
+
+        let mut chars = s.chars();
+        let first = chars.next();
+        if let Some(align) = chars.next().and_then(parse_align) {
+            this.fill = first;
+            this.align = Some(align);
+            s = chars.as_str();
+        } else if let Some(align) = first.and_then(parse_align) {
+            this.align = Some(align);
+            s = &s[1..];
+        }
+
+        if let Some(sign) = s.chars().next().and_then(|ch| match ch {
+            '+' => Some(Sign::Plus),
+            '-' => Some(Sign::Minus),
+            ' ' => Some(Sign::Space),
+            _ => None,
+        }) {
+            this.sign = Some(sign);
+            s = &s[1..];
+        }
+
+        if let Some(rest) = s.strip_prefix('z') {
+            this.coerce_zero = true;
+            s = rest;
+        }
+        if let Some(rest) = s.strip_prefix('#') {
+            this.alternate = true;
+            s = rest;
+        }
+        if let Some(rest) = s.strip_prefix('0') {
+            this.zero = true;
+            s = rest;
+        }
+
+        let width;
+        (width, s) = parse_usize(s)?;
+        this.width = width.unwrap_or(0);
+
+        if let Some(sep @ (',' | '_')) = s.chars().next() {
+            this.grouping = Some(sep);
+            s = &s[1..];
+        }
+
+        if let Some(rest) = s.strip_prefix('.') {
+            let (precision, rest) = parse_usize(rest)?;
+            let precision = precision.ok_or(PySpecError::NotAllowed("format specifier missing precision"))?;
+            this.precision = Some(precision.try_into().map_err(|_| PySpecError::Overflow)?);
+            s = rest;
+        }
+
+        let mut chars = s.chars();
+        if let Some(ty) = chars.next() {
+            if !"bcdeEfFgGnosxX%".contains(ty) {
+                return Err(PySpecError::UnexpectedChar(ty));
+            }
+            this.ty = Some(ty);
+        }
+        if let Some(ch) = chars.next() {
+            return Err(PySpecError::UnexpectedChar(ch));
+        }
+
+        Ok(this)
+    }
+
+    /// The part of spec supported by [`FormatterBuilder`]
+    ///
+    /// Grouping, `z`, presentation type, and `=` without fill `0` are lost
+    pub fn builder(&self) -> Result<FormatterBuilder<'static>, SpecError> {
+        let mut b = FormatterBuilder::new();
+
+        b.sign(self.sign)
+            .alternate(self.alternate)
+            .try_width(self.width)?
+            .precision(self.precision);
+
+        let fill = self.fill.or(self.zero.then_some('0'));
+        match self.align {
+            None | Some(PadAlign::AfterSign) => {
+                b.sign_aware_zero_pad(fill == Some('0'));
+            },
+            Some(align) => {
+                let align = match align {
+                    PadAlign::Left => Alignment::Left,
+                    PadAlign::Center => Alignment::Center,
+                    _ => Alignment::Right,
+                };
+                b.align(align);
+                if let Some(fill) = fill {
+                    b.fill(Fill::try_from(fill)?);
+                }
+            },
+        }
+
+        Ok(b)
+    }
+
+    /// Format the value like python `format(value, spec)`
+    pub fn format<W: Write>(&self, mut writer: W, value: &dyn PyArg) -> Result<(), PySpecError> {
+        match value.py_value() {
+            PyValue::Bool(b) if self.empty => self.format_str(&mut writer, if b { "True" } else { "False" }),
+            PyValue::Bool(b) => self.format_int(&mut writer, false, b.into()),
+            PyValue::Int(n) => self.format_int(&mut writer, n < 0, n.unsigned_abs()),
+            PyValue::UInt(n) => self.format_int(&mut writer, false, n),
+            PyValue::Float(n) => self.format_float(&mut writer, n),
+            PyValue::Str(s) => self.format_str(&mut writer, s),
+            PyValue::Char(ch) => self.format_str(&mut writer, ch.encode_utf8(&mut [0; 4])),
+        }
+    }
+
+    /// Display the value like python `format(value, spec)`, errors are [`fmt::Error`]
+    pub fn display<'a>(&'a self, value: &'a dyn PyArg) -> impl fmt::Display + 'a {
+        PyDisplay(self, value)
+    }
+
+    fn pad(&self, numeric: bool) -> Pad {
+        let default = if numeric { PadAlign::Right } else { PadAlign::Left };
+        let align = match self.align {
+            Some(align) => align,
+            None if self.zero && numeric => PadAlign::AfterSign,
+            None => default,
+        };
+        let fill = self.fill.or(self.zero.then_some('0')).unwrap_or(' ');
+        Pad { fill, align, width: self.width }
+    }
+
+    fn sign_str(&self, negative: bool) -> &'static str {
+        match (negative, self.sign) {
+            (true, _) => "-",
+            (false, Some(Sign::Plus)) => "+",
+            (false, Some(Sign::Space)) => " ",
+            (false, _) => "",
+        }
+    }
+
+    fn grouping(&self, size: u8) -> Option<Grouping> {
+        self.grouping.map(|separator| Grouping { separator, size: GroupSize::new(size) })
+    }
+
+    fn format_str(&self, w: &mut dyn Write, s: &str) -> Result<(), PySpecError> {
+        if !matches!(self.ty, None | Some('s')) {
+            return Err(PySpecError::UnknownType { ty: self.ty.unwrap(), value: "str" });
+        }
+        if self.sign.is_some() {
+            return Err(PySpecError::NotAllowed("sign not allowed in string format specifier"));
+        }
+        if self.alternate {
+            return Err(PySpecError::NotAllowed("alternate form (#) not allowed in string format specifier"));
+        }
+        if self.coerce_zero {
+            return Err(PySpecError::NotAllowed("negative zero coercion (z) not allowed in string format specifier"));
+        }
+        if self.align == Some(PadAlign::AfterSign) {
+            return Err(PySpecError::NotAllowed("'=' alignment not allowed in string format specifier"));
+        }
+        if let Some(sep) = self.grouping {
+            return Err(PySpecError::NotAllowed(if sep == ',' {
+                "cannot specify ',' with 's'"
+            } else {
+                "cannot specify '_' with 's'"
+            }));
+        }
+
+        let s = match self.precision.and_then(|precision| s.char_indices().nth(precision.into())) {
+            Some((end, _)) => &s[..end],
+            None => s,
+        };
+        let body = |w: &mut dyn Write| w.write_str(s);
+        Measure::new(10, &body)?.write(w, "", "", None, &self.pad(false), &body)?;
+        Ok(())
+    }
+
+    fn format_int(&self, w: &mut dyn Write, negative: bool, n: u128) -> Result<(), PySpecError> {
+        let ty = self.ty.unwrap_or('d');
+        if matches!(ty, 'e' | 'E' | 'f' | 'F' | 'g' | 'G' | '%') {
+            let n = n as f64;
+            return self.format_float(w, if negative { -n } else { n });
+        }
+        if ty == 's' {
+            return Err(PySpecError::UnknownType { ty, value: "int" });
+        }
+        if self.precision.is_some() {
+            return Err(PySpecError::NotAllowed("precision not allowed in integer format specifier"));
+        }
+        if self.coerce_zero {
+            return Err(PySpecError::NotAllowed("negative zero coercion (z) not allowed in integer format specifier"));
+        }
+        match (self.grouping, ty) {
+            (Some(','), 'b' | 'o' | 'x' | 'X' | 'c' | 'n') | (Some('_'), 'c' | 'n') => {
+                return Err(PySpecError::NotAllowed("cannot specify grouping with the presentation type"));
+            },
+            _ => (),
+        }
+
+        let pad = self.pad(true);
+        let (radix, prefix) = match ty {
+            'b' => (2, "0b"),
+            'o' => (8, "0o"),
+            'x' => (16, "0x"),
+            'X' => (16, "0X"),
+            'c' => {
+                if self.sign.is_some() {
+                    return Err(PySpecError::NotAllowed("sign not allowed with integer format specifier 'c'"));
+                }
+                if self.alternate {
+                    return Err(PySpecError::NotAllowed("alternate form (#) not allowed with integer format specifier 'c'"));
+                }
+                let ch = u32::try_from(n).ok()
+                    .filter(|_| !negative)
+                    .and_then(char::from_u32)
+                    .ok_or(PySpecError::CharOverflow)?;
+                let body = |w: &mut dyn Write| w.write_char(ch);
+                Measure::new(10, &body)?.write(w, "", "", None, &pad, &body)?;
+                return Ok(());
+            },
+            _ => (10, ""),
+        };
+        let prefix = if self.alternate { prefix } else { "" };
+        let grouping = self.grouping(if radix == 10 { 3 } else { 4 });
+
+        let body = |w: &mut dyn Write| match ty {
+            'b' => write!(w, "{n:b}"),
+            'o' => write!(w, "{n:o}"),
+            'x' => write!(w, "{n:x}"),
+            'X' => write!(w, "{n:X}"),
+            _ => write!(w, "{n}"),
+        };
+        Measure::new(radix, &body)?
+            .write(w, self.sign_str(negative), prefix, grouping.as_ref(), &pad, &body)?;
+        Ok(())
+    }
+
+    fn format_float(&self, w: &mut dyn Write, n: f64) -> Result<(), PySpecError> {
+        let ty = self.ty;
+        if !matches!(ty, None | Some('e' | 'E' | 'f' | 'F' | 'g' | 'G' | 'n' | '%')) {
+            return Err(PySpecError::UnknownType { ty: ty.unwrap(), value: "float" });
+        }
+        if ty == Some('n') && self.grouping.is_some() {
+            return Err(PySpecError::NotAllowed("cannot specify grouping with 'n'"));
+        }
+
+        let upper = matches!(ty, Some('E' | 'F' | 'G'));
+        let pad = self.pad(true);
+        let negative = n.is_sign_negative() && !n.is_nan();
+        let x = n.abs();
+
+        if !x.is_finite() {
+            let body = |w: &mut dyn Write| {
+                w.write_str(match (x.is_nan(), upper) {
+                    (true, false) => "nan",
+                    (true, true) => "NAN",
+                    (false, false) => "inf",
+                    (false, true) => "INF",
+                })?;
+                if ty == Some('%') { w.write_char('%')?; }
+                Ok(())
+            };
+            Measure::new(10, &body)?
+                .write(w, self.sign_str(negative), "", None, &pad, &body)?;
+            return Ok(());
+        }
+
+        let form = FloatForm::new(x, ty, self.precision, self.alternate)?;
+        let body = |w: &mut dyn Write| form.write(w, upper);
+        let measure = Measure::new(10, &body)?;
+        let negative = negative && (measure.nonzero || !self.coerce_zero);
+
+        measure.write(w, self.sign_str(negative), "", self.grouping(3).as_ref(), &pad, &body)?;
+        Ok(())
+    }
+}
+
+struct PyDisplay<'a>(&'a PySpec, &'a dyn PyArg);
+
+impl fmt::Display for PyDisplay<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        self.0.format(f, self.1).map_err(|_| fmt::Error)
+    }
+}
+
+/// Resolved float notation of python presentation type
+#[derive(Debug, Clone, Copy)]
+struct FloatForm {
+    x: f64,
+    exp: bool,
+    precision: Option<u16>,
+    trim: bool,
+    force_point: bool,
+    dot_zero: bool,
+    percent: bool,
+}
+
+impl FloatForm {
+    fn new(x: f64, ty: Option<char>, precision: Option<u16>, alternate: bool) -> Result<Self, fmt::Error> {
+        let mut form = Self {
+            x,
+            exp: false,
+            precision: Some(precision.unwrap_or(6)),
+            trim: false,
+            force_point: alternate,
+            dot_zero: false,
+            percent: false,
+        };
+
+        match ty {
+            Some('f' | 'F') => (),
+            Some('%') => {
+                form.x *= 100.0;
+                form.percent = true;
+            },
+            Some('e' | 'E') => form.exp = true,
+            None if precision.is_none() => {
+                // like python repr
+                let exp = exponent(&x, RoundingMode::HalfEven, None)?;
+                form.exp = !(-4..16).contains(&exp);
+                form.precision = None;
+                form.dot_zero = true;
+            },
+            _ => {
+                // general format
+                let p = precision.unwrap_or(6).max(1);
+                let exp = exponent(&x, RoundingMode::HalfEven, Some(p - 1))?;
+                let dot_zero = ty.is_none();
+                let limit = i32::from(p) - i32::from(dot_zero);
+
+                form.trim = !alternate;
+                form.dot_zero = dot_zero;
+                if (-4..limit).contains(&exp) {
+                    form.precision = Some((i32::from(p) - 1 - exp) as u16);
+                } else {
+                    form.exp = true;
+                    form.precision = Some(p - 1);
+                }
+            },
+        }
+
+        Ok(form)
+    }
+
+    fn write(&self, w: &mut dyn Write, upper: bool) -> fmt::Result {
+        let mut fw = FloatWriter::new(w, self.trim, self.force_point, self.dot_zero)
+            .exp_style(ExpStyle { min_digits: 2, always_sign: true, upper, hidden: false });
+        let x = self.x;
+        let mut b = FormatterBuilder::new();
+        b.precision(self.precision);
+
+        if self.exp {
+            b.with(&mut fw, |f| fmt::LowerExp::fmt(&x, f))?;
+        } else {
+            b.with(&mut fw, |f| fmt::Display::fmt(&x, f))?;
+        }
+        fw.finish()?;
+
+        if self.percent {
+            w.write_char('%')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[track_caller]
+    fn check(spec: &str, value: &dyn PyArg, exp: &str) {
+        let mut out = String::new();
+        PySpec::parse(spec).unwrap().format(&mut out, value).unwrap();
+        assert_eq!(out, exp, "spec {spec:?}");
+    }
+
+    #[test]
+    fn float() {
+        check("", &-1234.5, "-1234.5");
+        check("e", &-1234.5, "-1.234500e+03");
+        check(".3", &-1234.5, "-1.23e+03");
+        check(".3g", &-1234.5, "-1.23e+03");
+        check("g", &-1234.5, "-1234.5");
+        check("%", &-1234.5, "-123450.000000%");
+        check(".1%", &-1234.5, "-123450.0%");
+        check("=+10", &-1234.5, "-   1234.5");
+        check("+08,.2f", &-1234.5, "-1,234.50");
+        check("*^12,.2f", &-1234.5, "*-1,234.50**");
+        check("#g", &-1234.5, "-1234.50");
+        check(".0", &-1234.5, "-1e+03");
+        check("#.0f", &-1234.5, "-1234.");
+        check("08.2%", &1.5, "0150.00%");
+        check("08.1e", &-3, "-3.0e+00");
+        check(".2f", &3, "3.00");
+    }
+
+    #[test]
+    fn float_repr() {
+        let cases = [
+            (1e16, "1e+16", "1e+16", "1e+16", "1.00e+16", "1E+16"),
+            (1e15, "1000000000000000.0", "1e+15", "1e+15", "1.00e+15", "1E+15"),
+            (1e-5, "1e-05", "1e-05", "1e-05", "1.00e-05", "1E-05"),
+            (1e-4, "0.0001", "0.0001", "0.0001", "1.00e-04", "0.0001"),
+            (0.0001234, "0.0001234", "0.000123", "0.0001234", "1.23e-04", "0.0001234"),
+            (123456789.0, "123456789.0", "1.23e+08", "1.23457e+08", "1.23e+08", "1.23457E+08"),
+            (1.0, "1.0", "1.0", "1", "1.00e+00", "1"),
+            (f64::NAN, "nan", "nan", "nan", "nan", "NAN"),
+            (f64::NEG_INFINITY, "-inf", "-inf", "-inf", "-inf", "-INF"),
+            (-0.0, "-0.0", "-0.0", "-0", "-0.00e+00", "-0"),
+        ];
+        for (n, repr, p3, g, e2, upper_g) in cases {
+            check("", &n, repr);
+            check(".3", &n, p3);
+            check("g", &n, g);
+            check(".2e", &n, e2);
+            check("G", &n, upper_g);
+        }
+        check("", &5e-324, "5e-324");
+        check("", &1.7976931348623157e308, "1.7976931348623157e+308");
+        check("", &1e22, "1e+22");
+        check(".2", &123.0, "1.2e+02");
+        check(".2", &12.0, "1.2e+01");
+        check(".1", &1.0, "1e+00");
+        check("#.1", &1.0, "1.e+00");
+        check("#.3g", &100.0, "100.");
+        check("#", &1.0, "1.0");
+        check("#", &1e16, "1.e+16");
+        check(".4", &123.0, "123.0");
+    }
+
+    #[test]
+    fn float_special() {
+        check("+", &f64::NAN, "+nan");
+        check("", &-f64::NAN, "nan");
+        check("010", &f64::INFINITY, "0000000inf");
+        check("010,", &f64::INFINITY, "0000000inf");
+        check("z.2f", &-0.0001, "0.00");
+        check("z", &-0.0, "0.0");
+        check("zg", &-0.0, "0");
+        check(".0f", &0.5, "0");
+        check(".0f", &2.5, "2");
+        check(",.0f", &1e100, "10,000,000,000,000,000,159,028,911,097,599,180,468,360,808,563,945,281,389,781,327,557,747,838,772,170,381,060,813,469,985,856,815,104");
+    }
+
+    #[test]
+    fn int() {
+        check("_x", &48879, "beef");
+        check("#X", &48879, "0XBEEF");
+        check("#_b", &48879, "0b1011_1110_1110_1111");
+        check("010_x", &48879, "0_0000_beef");
+        check("#010_x", &48879, "0x000_beef");
+        check("#o", &255, "0o377");
+        check("#x", &-255, "-0xff");
+        check("#010b", &255, "0b11111111");
+        check("_", &10u128.pow(30), "1_000_000_000_000_000_000_000_000_000_000");
+        check("e", &10u128.pow(30), "1.000000e+30");
+        check(",d", &12345678, "12,345,678");
+        check("c", &5, "\x05");
+        check("=5", &5, "    5");
+        check("08,", &1234, "0,001,234");
+        check("0=10,", &1234, "00,001,234");
+        check("x=10,", &1234, "xxxxx1,234");
+        check("0>10,", &1234, "000001,234");
+        check("0<10,", &1234, "1,23400000");
+        check(" ", &1, " 1");
+        check("", &i128::MIN, "-170141183460469231731687303715884105728");
+    }
+
+    #[test]
+    fn str_and_bool() {
+        check("^6", &"ab", "  ab  ");
+        check("05", &"ab", "ab000");
+        check(".1", &"ab", "a");
+        check(">3", &'c', "  c");
+        check("", &true, "True");
+        check("d", &true, "1");
+        check("^3", &false, " 0 ");
+        check("%", &true, "100.000000%");
+    }
+
+    #[test]
+    fn errors() {
+        let err = |spec: &str, value: &dyn PyArg| {
+            PySpec::parse(spec).and_then(|spec| spec.format(String::new(), value)).unwrap_err()
+        };
+        assert_eq!(err(".2d", &5), PySpecError::NotAllowed("precision not allowed in integer format specifier"));
+        assert_eq!(err("=5s", &"a"), PySpecError::NotAllowed("'=' alignment not allowed in string format specifier"));
+        assert_eq!(err("+", &"a"), PySpecError::NotAllowed("sign not allowed in string format specifier"));
+        assert!(matches!(err(",s", &"a"), PySpecError::NotAllowed(_)));
+        assert!(matches!(err(",x", &5), PySpecError::NotAllowed(_)));
+        assert!(matches!(err("+c", &5), PySpecError::NotAllowed(_)));
+        assert!(matches!(err("z5d", &5), PySpecError::NotAllowed(_)));
+        assert_eq!(err("d", &1.0), PySpecError::UnknownType { ty: 'd', value: "float" });
+        assert_eq!(err("x", &"a"), PySpecError::UnknownType { ty: 'x', value: "str" });
+        assert_eq!(err("c", &-1), PySpecError::CharOverflow);
+        assert_eq!(err("5y", &1), PySpecError::UnexpectedChar('y'));
+        assert_eq!(err("dd", &1), PySpecError::UnexpectedChar('d'));
+        assert_eq!(err(".", &1.0), PySpecError::NotAllowed("format specifier missing precision"));
+        assert_eq!(err(".70000", &1.0), PySpecError::Overflow);
+    }
+
+    #[test]
+    fn builder() {
+        let b = PySpec::parse("0<+#8.3").unwrap().builder().unwrap();
+        b.with(String::new(), |f| {
+            assert_eq!(f.fill(), '0');
+            assert_eq!(f.align(), Some(Alignment::Left));
+            assert!(f.sign_plus());
+            assert!(f.alternate());
+            assert_eq!(f.width(), Some(8));
+            assert_eq!(f.precision(), Some(3));
+            Ok(())
+        }).unwrap();
+
+        let b = PySpec::parse("010,").unwrap().builder().unwrap();
+        b.with(String::new(), |f| {
+            assert!(f.sign_aware_zero_pad());
+            Ok(())
+        }).unwrap();
+
+        assert_eq!(PySpec::parse("*<").unwrap().builder().unwrap_err(), SpecError::UnsupportedFill('*'));
+    }
+}
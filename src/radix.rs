@@ -0,0 +1,169 @@
+//! Integer adapter of arbitrary radix, like `z` `0h1f` `16#1F#`
+
+use core::fmt::{self, Display, Formatter, Write};
+
+use crate::number::{Measure, Pad, PadAlign};
+
+/// Integer of [`Radix`], all primitive integers
+pub trait RadixInt: Copy + sealed::Sealed {
+    #[doc(hidden)]
+    fn sign_magnitude(self) -> (bool, u128);
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+macro_rules! impl_radix_int {
+    (signed $($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+        impl RadixInt for $t {
+            fn sign_magnitude(self) -> (bool, u128) {
+                (self < 0, self.unsigned_abs() as u128)
+            }
+        }
+    )*};
+    (unsigned $($t:ty),*) => {$(
+        impl sealed::Sealed for $t {}
+        impl RadixInt for $t {
+            fn sign_magnitude(self) -> (bool, u128) {
+                (false, self as u128)
+            }
+        }
+    )*};
+}
+impl_radix_int!(signed i8, i16, i32, i64, i128, isize);
+impl_radix_int!(unsigned u8, u16, u32, u64, u128, usize);
+
+/// Format integer in radix 2 to 36, negative is `-` and magnitude
+///
+/// - Alternate writes the prefix, default is `0b` `0o` `0x` for radix 2 8 16,
+///   and empty for others
+/// - Sign, sign aware zero pad, width, fill and align are like `{:#010x}`
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// use std::fmt::Display;
+/// assert_eq!(format!("{}", Radix::new(35, 36)), "z");
+/// assert_eq!(format!("{:#}", Radix::new(-255, 16).upper(true)), "-0xFF");
+/// assert_eq!(format!("{:#010}", Radix::new(31, 16).prefix("0h")), "0h0000001f");
+/// assert_eq!(format!("{:>+6}", Radix::new(5, 3)), "   +12");
+///
+/// let mut writter = String::new();
+/// FormatterBuilder::new().alternate(true).width(8).with(&mut writter, |f| {
+///     Radix::new(31u8, 16).prefix("16#").fmt(f)
+/// }).unwrap();
+/// assert_eq!(writter, "   16#1f");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Radix<'a> {
+    negative: bool,
+    magnitude: u128,
+    radix: u32,
+    prefix: &'a str,
+    upper: bool,
+}
+
+impl<'a> Radix<'a> {
+    /// # Panics
+    ///
+    /// - panic when radix is not in `2..=36`
+    #[track_caller]
+    pub fn new(value: impl RadixInt, radix: u32) -> Self {
+        assert!((2..=36).contains(&radix), "radix must be in 2..=36, got {radix}");
+        let (negative, magnitude) = value.sign_magnitude();
+        let prefix = match radix {
+            2 => "0b",
+            8 => "0o",
+            16 => "0x",
+            _ => "",
+        };
+        Self { negative, magnitude, radix, prefix, upper: false }
+    }
+
+    /// Prefix written in alternate mode
+    pub fn prefix(mut self, prefix: &'a str) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Upper case digits, like `FF`
+    pub fn upper(mut self, upper: bool) -> Self {
+        self.upper = upper;
+        self
+    }
+
+    /// Digits into the end of buffer, returns the start
+    fn digits(&self, buf: &mut [u8; 128]) -> usize {
+        let radix = u128::from(self.radix);
+        let mut n = self.magnitude;
+        let mut start = buf.len();
+        loop {
+            let digit = char::from_digit((n % radix) as u32, self.radix).unwrap();
+            start -= 1;
+            buf[start] = if self.upper { digit.to_ascii_uppercase() } else { digit } as u8;
+            n /= radix;
+            if n == 0 {
+                return start;
+            }
+        }
+    }
+}
+
+impl Display for Radix<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut buf = [0; 128];
+        let start = self.digits(&mut buf);
+        let digits = core::str::from_utf8(&buf[start..]).unwrap();
+
+        let sign = match (self.negative, f.sign_plus()) {
+            (true, _) => "-",
+            (false, true) => "+",
+            (false, false) => "",
+        };
+        let prefix = if f.alternate() { self.prefix } else { "" };
+        let pad = Pad::from_formatter(f, PadAlign::Right);
+        let body = |w: &mut dyn Write| w.write_str(digits);
+
+        Measure::new(self.radix, &body)?.write(f, sign, prefix, None, &pad, &body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FormatterBuilder, Sign};
+
+    #[test]
+    fn radix() {
+        assert_eq!(format!("{}", Radix::new(0, 2)), "0");
+        assert_eq!(format!("{}", Radix::new(u128::MAX, 2)), "1".repeat(128));
+        assert_eq!(format!("{}", Radix::new(i64::MIN, 36)), "-1y2p0ij32e8e8");
+        assert_eq!(format!("{}", Radix::new(-7i8, 3)), "-21");
+        assert_eq!(format!("{:#}", Radix::new(255u8, 8)), "0o377");
+        assert_eq!(format!("{:#}", Radix::new(255u8, 10)), "255");
+        assert_eq!(format!("{}", Radix::new(1295, 36).upper(true)), "ZZ");
+    }
+
+    #[test]
+    fn padding() {
+        let radix = Radix::new(-0x1f, 16);
+        assert_eq!(format!("{radix:#010}"), "-0x000001f");
+        assert_eq!(format!("{radix:#10}"), "     -0x1f");
+        assert_eq!(format!("{radix:*<#8}"), "-0x1f***");
+        assert_eq!(format!("{radix:^7}"), "  -1f  ");
+        assert_eq!(format!("{:+#06}", Radix::new(5, 3).prefix("3#")), "+3#012");
+
+        let mut out = String::new();
+        FormatterBuilder::new().sign(Sign::Space).with(&mut out, |f| Radix::new(5, 3).fmt(f)).unwrap();
+        assert_eq!(out, " 12");
+    }
+
+    #[test]
+    #[should_panic = "radix must be in 2..=36"]
+    fn invalid_radix() {
+        Radix::new(1, 37);
+    }
+}
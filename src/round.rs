@@ -0,0 +1,318 @@
+//! Rounding modes of float precision
+//!
+//! Core prints the exact decimal expansion with a large precision,
+//! then it is rounded while streaming, in two passes over the same output:
+//! first decide the rounding, then write the digits with the carry
+
+use core::fmt::{self, Display, LowerExp, Write};
+
+use crate::FormatterBuilder;
+
+/// Precision of the exact decimal expansion, longer than the fraction digits of [`f64::MIN_POSITIVE`] subnormals
+const EXACT: u16 = 1100;
+
+/// Rounding mode of float precision, used by [`FormatterBuilder::rounding`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingMode {
+    /// Ties to even, like core
+    #[default]
+    HalfEven,
+    /// Ties toward positive infinity
+    HalfUp,
+    /// Ties away from zero
+    HalfAwayFromZero,
+    /// Truncate
+    TowardZero,
+    /// Toward negative infinity
+    Floor,
+    /// Toward positive infinity
+    Ceiling,
+}
+
+impl RoundingMode {
+    /// Round up the magnitude, `first` is the first dropped digit, `rest` is any nonzero digit after it
+    fn round_up(self, negative: bool, last_odd: bool, first: u8, rest: bool) -> bool {
+        let nonzero = first != 0 || rest;
+        let above_half = first > 5 || first == 5 && rest;
+        match self {
+            Self::HalfEven => above_half || first == 5 && last_odd,
+            Self::HalfUp => above_half || first == 5 && !negative,
+            Self::HalfAwayFromZero => first >= 5,
+            Self::TowardZero => false,
+            Self::Floor => negative && nonzero,
+            Self::Ceiling => !negative && nonzero,
+        }
+    }
+}
+
+impl FormatterBuilder<'_> {
+    /// Rounding mode when precision truncates the output of [`float`](FormatterBuilder::float),
+    /// the exact decimal value is rounded
+    ///
+    /// Floats in [`with`](FormatterBuilder::with) are rounded by core, half to even
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut b = FormatterBuilder::new();
+    /// b.precision(1);
+    ///
+    /// let mut writter = String::new();
+    /// b.float(&mut writter, 0.25).unwrap();
+    /// assert_eq!(writter, "0.2");
+    ///
+    /// let mut writter = String::new();
+    /// b.rounding(RoundingMode::HalfAwayFromZero).float(&mut writter, 0.25).unwrap();
+    /// assert_eq!(writter, "0.3");
+    ///
+    /// // 0.15 is 0.1499999999999999944488848768742172978818416595458984375
+    /// let mut writter = String::new();
+    /// b.float(&mut writter, 0.15).unwrap();
+    /// assert_eq!(writter, "0.1");
+    ///
+    /// let mut writter = String::new();
+    /// b.rounding(RoundingMode::Floor).float(&mut writter, -0.01).unwrap();
+    /// assert_eq!(writter, "-0.1");
+    /// ```
+    pub fn rounding(&mut self, rounding: RoundingMode) -> &mut Self {
+        self.rounding = rounding;
+        self
+    }
+}
+
+/// Write like core with the precision of `b`, but rounded by the mode
+pub(crate) fn write_rounded<T>(
+    w: &mut dyn Write,
+    x: &T,
+    b: &FormatterBuilder<'_>,
+    exp: bool,
+    mode: RoundingMode,
+) -> fmt::Result
+where
+    T: Display + LowerExp + ?Sized,
+{
+    let core = |b: &FormatterBuilder<'_>, w: &mut dyn Write| if exp {
+        b.with(w, |f| LowerExp::fmt(x, f))
+    } else {
+        b.with(w, |f| Display::fmt(x, f))
+    };
+
+    let Some(precision) = b.precision.filter(|_| mode != RoundingMode::HalfEven) else {
+        return core(b, w);
+    };
+    let mut exact = b.clone();
+    exact.precision(precision.saturating_add(1).max(EXACT));
+
+    let mut probe = Probe { precision: precision.into(), ..Probe::default() };
+    core(&exact, &mut probe)?;
+
+    let round_up = mode.round_up(probe.negative, probe.last % 2 == 1, probe.first, probe.rest);
+    let mut emit = Emit {
+        inner: w,
+        precision: precision.into(),
+        carry: round_up.then_some(probe.last_non_nine),
+        scientific: exp,
+        exp_carry: false,
+        digits: 0,
+        state: State::Int,
+        exp: None,
+    };
+    core(&exact, &mut emit)?;
+    emit.finish()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum State {
+    #[default]
+    Int,
+    Fraction(usize),
+    Exp,
+}
+
+/// First pass, find the kept digits and the dropped digits
+#[derive(Debug, Default)]
+struct Probe {
+    precision: usize,
+    state: State,
+    negative: bool,
+    /// Count of kept digits
+    digits: usize,
+    /// Last kept digit
+    last: u8,
+    /// Index of the last kept digit that is not 9
+    last_non_nine: Option<usize>,
+    /// First dropped digit
+    first: u8,
+    /// Any nonzero digit after the first dropped digit
+    rest: bool,
+}
+
+impl Write for Probe {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().try_for_each(|ch| self.write_char(ch))
+    }
+
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        let digit = ch.to_digit(10).map(|digit| digit as u8);
+        match (self.state, digit) {
+            (State::Int, None) if ch == '-' => self.negative = true,
+            (State::Int, None) if ch == '.' => self.state = State::Fraction(0),
+            (State::Int | State::Fraction(_), None) if ch == 'e' || ch == 'E' => self.state = State::Exp,
+            (State::Fraction(i), Some(digit)) if i >= self.precision => {
+                self.state = State::Fraction(i + 1);
+                if i == self.precision {
+                    self.first = digit;
+                } else {
+                    self.rest |= digit != 0;
+                }
+            },
+            (State::Int | State::Fraction(_), Some(digit)) => {
+                if let State::Fraction(i) = self.state {
+                    self.state = State::Fraction(i + 1);
+                }
+                if digit != 9 {
+                    self.last_non_nine = Some(self.digits);
+                }
+                self.last = digit;
+                self.digits += 1;
+            },
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+/// Second pass, write the kept digits with the carry
+struct Emit<'a> {
+    inner: &'a mut dyn Write,
+    precision: usize,
+    /// Round up, at the index of last non-nine digit, [`None`] is all nines
+    carry: Option<Option<usize>>,
+    scientific: bool,
+    /// All nines in scientific, the exponent is increased
+    exp_carry: bool,
+    digits: usize,
+    state: State,
+    /// Exponent of scientific, written at the end
+    exp: Option<(bool, i32)>,
+}
+
+impl Emit<'_> {
+    fn write_digit(&mut self, digit: u8) -> fmt::Result {
+        let index = self.digits;
+        self.digits += 1;
+
+        let digit = match self.carry {
+            Some(Some(last)) if index == last => digit + 1,
+            Some(Some(last)) if index > last => 0,
+            Some(None) if index == 0 => {
+                // all nines, like `99.96` into `100.0` and `9.96e1` into `1.00e2`
+                if self.scientific {
+                    self.exp_carry = true;
+                    1
+                } else {
+                    self.inner.write_char('1')?;
+                    0
+                }
+            },
+            Some(None) => 0,
+            _ => digit,
+        };
+        self.inner.write_char(char::from(b'0' + digit))
+    }
+
+    fn finish(&mut self) -> fmt::Result {
+        if let Some((negative, exp)) = self.exp {
+            let exp = if negative { -exp } else { exp } + i32::from(self.exp_carry);
+            write!(self.inner, "e{exp}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Write for Emit<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().try_for_each(|ch| self.write_char(ch))
+    }
+
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        let digit = ch.to_digit(10).map(|digit| digit as u8);
+        match (self.state, digit) {
+            (State::Int, None) if ch == '.' => {
+                self.state = State::Fraction(0);
+                if self.precision != 0 {
+                    self.inner.write_char('.')?;
+                }
+            },
+            (State::Int | State::Fraction(_), None) if ch == 'e' || ch == 'E' => {
+                self.state = State::Exp;
+                self.exp = Some((false, 0));
+            },
+            (State::Exp, None) if ch == '-' => self.exp = Some((true, 0)),
+            (State::Exp, Some(digit)) => {
+                if let Some((_, exp)) = &mut self.exp {
+                    *exp = *exp * 10 + i32::from(digit);
+                }
+            },
+            (State::Fraction(i), Some(_)) if i >= self.precision => self.state = State::Fraction(i + 1),
+            (State::Fraction(i), Some(digit)) => {
+                self.state = State::Fraction(i + 1);
+                self.write_digit(digit)?;
+            },
+            (State::Int, Some(digit)) => self.write_digit(digit)?,
+            (_, None) => self.inner.write_char(ch)?,
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FloatStyle;
+
+    fn run(mode: RoundingMode, style: FloatStyle, precision: u16, x: f64) -> String {
+        let mut out = String::new();
+        FormatterBuilder::new()
+            .rounding(mode)
+            .float_style(style)
+            .precision(precision)
+            .float(&mut out, x)
+            .unwrap();
+        out
+    }
+
+    #[test]
+    fn modes() {
+        use RoundingMode::*;
+        let cases = [
+            (0.25, 1, ["0.2", "0.3", "0.3", "0.2", "0.2", "0.3"]),
+            (-0.25, 1, ["-0.2", "-0.2", "-0.3", "-0.2", "-0.3", "-0.2"]),
+            (0.15, 1, ["0.1", "0.1", "0.1", "0.1", "0.1", "0.2"]),
+            (2.5, 0, ["2", "3", "3", "2", "2", "3"]),
+            (-1.01, 1, ["-1.0", "-1.0", "-1.0", "-1.0", "-1.1", "-1.0"]),
+            (9.96, 1, ["10.0", "10.0", "10.0", "9.9", "9.9", "10.0"]),
+            (-99.99, 0, ["-100", "-100", "-100", "-99", "-100", "-99"]),
+            (f64::NAN, 1, ["NaN"; 6]),
+        ];
+        for (x, precision, exps) in cases {
+            for (mode, exp) in [HalfEven, HalfUp, HalfAwayFromZero, TowardZero, Floor, Ceiling].into_iter().zip(exps) {
+                assert_eq!(run(mode, FloatStyle::Fixed, precision, x), exp, "{x} {mode:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn notations() {
+        use RoundingMode::*;
+        assert_eq!(run(Ceiling, FloatStyle::Scientific, 1, 9.96), "1.0e1");
+        assert_eq!(run(Ceiling, FloatStyle::Scientific, 1, 1234.0), "1.3e3");
+        assert_eq!(run(TowardZero, FloatStyle::Scientific, 2, 0.0), "0.00e0");
+        assert_eq!(run(Ceiling, FloatStyle::General, 2, 99.1), "1e2");
+        assert_eq!(run(TowardZero, FloatStyle::General, 2, 99.9), "99");
+        assert_eq!(run(Ceiling, FloatStyle::Engineering, 1, 999.91), "1.0e3");
+        assert_eq!(run(HalfUp, FloatStyle::Fixed, 3, 1e-320), "0.000");
+        assert_eq!(run(Ceiling, FloatStyle::Fixed, 3, 1e-320), "0.001");
+    }
+}
@@ -0,0 +1,265 @@
+use core::fmt::{self, Write};
+
+use crate::FormatterBuilder;
+
+/// Output of NaN, infinities and zeros in [`FormatterBuilder::float`]
+///
+/// The default is like core
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// let special = SpecialValues::new()
+///     .with_nan("")
+///     .with_infinity("Infinity", "-Infinity")
+///     .with_negative_zero(false);
+/// assert_eq!(special.nan(), "");
+/// assert_eq!(SpecialValues::default(), SpecialValues::CORE);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpecialValues<'a> {
+    nan: &'a str,
+    infinity: &'a str,
+    neg_infinity: &'a str,
+    negative_zero: bool,
+    zero_sign: bool,
+}
+
+impl Default for SpecialValues<'_> {
+    fn default() -> Self {
+        Self::CORE
+    }
+}
+
+impl<'a> SpecialValues<'a> {
+    /// Like core, `NaN` `inf` `-inf` `-0` `+0`
+    pub const CORE: Self = Self {
+        nan: "NaN",
+        infinity: "inf",
+        neg_infinity: "-inf",
+        negative_zero: true,
+        zero_sign: true,
+    };
+
+    pub const fn new() -> Self {
+        Self::CORE
+    }
+
+    /// Output of NaN, sign is never written
+    pub const fn with_nan(mut self, nan: &'a str) -> Self {
+        self.nan = nan;
+        self
+    }
+
+    /// Output of positive and negative infinity,
+    /// the sign of [`Sign::Plus`](crate::Sign::Plus) or [`Sign::Space`](crate::Sign::Space) is written before positive infinity
+    pub const fn with_infinity(mut self, infinity: &'a str, neg_infinity: &'a str) -> Self {
+        self.infinity = infinity;
+        self.neg_infinity = neg_infinity;
+        self
+    }
+
+    /// Write `-` of negative zero, and of negative values rounded to zero like `-0.00`
+    pub const fn with_negative_zero(mut self, negative_zero: bool) -> Self {
+        self.negative_zero = negative_zero;
+        self
+    }
+
+    /// Write the sign of [`Sign::Plus`](crate::Sign::Plus) or [`Sign::Space`](crate::Sign::Space) on zero
+    pub const fn with_zero_sign(mut self, zero_sign: bool) -> Self {
+        self.zero_sign = zero_sign;
+        self
+    }
+
+    pub fn nan(&self) -> &'a str {
+        self.nan
+    }
+
+    pub fn infinity(&self) -> &'a str {
+        self.infinity
+    }
+
+    pub fn neg_infinity(&self) -> &'a str {
+        self.neg_infinity
+    }
+
+    pub fn negative_zero(&self) -> bool {
+        self.negative_zero
+    }
+
+    pub fn zero_sign(&self) -> bool {
+        self.zero_sign
+    }
+
+    /// Write the float output of `body` by the policy
+    pub(crate) fn write(
+        &self,
+        w: &mut dyn Write,
+        body: impl Fn(&mut dyn Write) -> fmt::Result,
+    ) -> fmt::Result {
+        let mut probe = Probe::default();
+        body(&mut probe)?;
+        let sign = probe.sign.filter(|&sign| sign != '-');
+
+        if probe.nan {
+            w.write_str(self.nan)
+        } else if probe.inf && probe.sign == Some('-') {
+            w.write_str(self.neg_infinity)
+        } else if probe.inf {
+            sign.map_or(Ok(()), |sign| w.write_char(sign))?;
+            w.write_str(self.infinity)
+        } else if !probe.nonzero {
+            let show = if probe.sign == Some('-') { self.negative_zero } else { self.zero_sign };
+            body(&mut DropSign { inner: w, drop: !show, started: false })
+        } else {
+            body(w)
+        }
+    }
+}
+
+impl<'a> FormatterBuilder<'a> {
+    /// Output policy of NaN, infinities and zeros in [`float`](FormatterBuilder::float),
+    /// still padded to the width
+    ///
+    /// It only applies to [`float`](FormatterBuilder::float), [`with`](FormatterBuilder::with) ignores it
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let mut b = FormatterBuilder::new();
+    /// b.sign(Sign::Plus).width(6).special_values(SpecialValues::new()
+    ///     .with_nan("null")
+    ///     .with_negative_zero(false)
+    ///     .with_zero_sign(false));
+    ///
+    /// let mut writter = String::new();
+    /// b.float(&mut writter, f64::NAN).unwrap();
+    /// assert_eq!(writter, "  null");
+    ///
+    /// let mut writter = String::new();
+    /// b.float(&mut writter, -0.0).unwrap();
+    /// assert_eq!(writter, "     0");
+    ///
+    /// let mut writter = String::new();
+    /// b.float(&mut writter, 1.0).unwrap();
+    /// assert_eq!(writter, "    +1");
+    /// ```
+    pub fn special_values(&mut self, special_values: impl Into<Option<SpecialValues<'a>>>) -> &mut Self {
+        self.special_values = special_values.into();
+        self
+    }
+}
+
+/// Kind of float output
+#[derive(Debug, Default)]
+struct Probe {
+    /// First char when it's a sign
+    sign: Option<char>,
+    started: bool,
+    nan: bool,
+    inf: bool,
+    /// Any nonzero digit in mantissa
+    nonzero: bool,
+    in_exp: bool,
+}
+
+impl Write for Probe {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().try_for_each(|ch| self.write_char(ch))
+    }
+
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        if !self.started && matches!(ch, '+' | '-' | ' ') {
+            self.sign = Some(ch);
+        }
+        self.started = true;
+
+        match ch {
+            'N' => self.nan = true,
+            'i' => self.inf = true,
+            'e' | 'E' => self.in_exp = true,
+            '1'..='9' if !self.in_exp => self.nonzero = true,
+            _ => (),
+        }
+        Ok(())
+    }
+}
+
+/// Drop the leading sign
+struct DropSign<'a> {
+    inner: &'a mut dyn Write,
+    drop: bool,
+    started: bool,
+}
+
+impl Write for DropSign<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        s.chars().try_for_each(|ch| self.write_char(ch))
+    }
+
+    fn write_char(&mut self, ch: char) -> fmt::Result {
+        let first = !self.started;
+        self.started = true;
+        if first && self.drop && matches!(ch, '+' | '-' | ' ') {
+            return Ok(());
+        }
+        self.inner.write_char(ch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Alignment, Sign};
+
+    fn run(b: &FormatterBuilder<'_>, x: f64) -> String {
+        let mut out = String::new();
+        b.float(&mut out, x).unwrap();
+        out
+    }
+
+    #[test]
+    fn special_values() {
+        let mut b = FormatterBuilder::new();
+        b.special_values(SpecialValues::new().with_nan("").with_infinity("Infinity", "-Infinity"));
+        assert_eq!(run(&b, f64::NAN), "");
+        assert_eq!(run(&b, f64::INFINITY), "Infinity");
+        assert_eq!(run(&b, f64::NEG_INFINITY), "-Infinity");
+        assert_eq!(run(b.sign(Sign::Plus), f64::INFINITY), "+Infinity");
+        assert_eq!(run(b.sign(Sign::Space), f64::INFINITY), " Infinity");
+        assert_eq!(run(&b, -0.0), "-0");
+        assert_eq!(run(b.sign(None).width(4), f64::NAN), "    ");
+        assert_eq!(run(b.align(Alignment::Center).width(10), f64::INFINITY), " Infinity ");
+
+        let nan = String::from("n/a");
+        let mut b = FormatterBuilder::new();
+        b.special_values(SpecialValues::new().with_nan(&nan));
+        assert_eq!(run(&b, f64::NAN), "n/a");
+    }
+
+    #[test]
+    fn zeros() {
+        let mut b = FormatterBuilder::new();
+        b.special_values(SpecialValues::new().with_negative_zero(false)).precision(2);
+        assert_eq!(run(&b, -0.0), "0.00");
+        assert_eq!(run(&b, -0.001), "0.00");
+        assert_eq!(run(&b, -0.01), "-0.01");
+        assert_eq!(run(b.sign(Sign::Plus), 0.0), "+0.00");
+
+        b.special_values(SpecialValues::new().with_zero_sign(false));
+        assert_eq!(run(&b, 0.0), "0.00");
+        assert_eq!(run(&b, -0.0), "-0.00");
+        assert_eq!(run(&b, 0.5), "+0.50");
+        assert_eq!(run(b.sign(Sign::Space).width(6), 0.0), "  0.00");
+        assert_eq!(run(b.sign_aware_zero_pad(true), 0.0), "000.00");
+    }
+
+    #[test]
+    fn default_align() {
+        let b = FormatterBuilder::new();
+        assert_eq!(run(b.clone().width(5), f64::NAN), "  NaN");
+        assert_eq!(run(b.clone().width(5), f64::NEG_INFINITY), " -inf");
+    }
+}
@@ -0,0 +1,179 @@
+use core::{fmt::{self, Formatter, Write}, marker::PhantomData};
+
+use crate::{Alignment, Fill, FormatterBuilder, Sign};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Alignment state of a [`TypedBuilder`].
+///
+/// Implemented only by [`Unaligned`] and [`Aligned`].
+pub trait AlignState: sealed::Sealed {}
+
+/// [`TypedBuilder`] state before [`align`](TypedBuilder::align) is called.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Unaligned;
+
+/// [`TypedBuilder`] state after [`align`](TypedBuilder::align) is called,
+/// [`fill`](TypedBuilder::fill) is only available in this state.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Aligned;
+
+impl sealed::Sealed for Unaligned {}
+impl sealed::Sealed for Aligned {}
+impl AlignState for Unaligned {}
+impl AlignState for Aligned {}
+
+/// Typestate [`FormatterBuilder`], setting fill without align is a compile error.
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// use std::fmt::Display;
+/// let mut output = String::new();
+/// TypedBuilder::new()
+///     .width(5)
+///     .align(Alignment::Right)
+///     .fill(Fill::Zero)
+///     .with(&mut output, |f| 2i32.fmt(f))
+///     .unwrap();
+/// assert_eq!(output, "00002");
+/// ```
+///
+/// ```compile_fail
+/// # use formatter_builder::*;
+/// TypedBuilder::new().fill(Fill::Zero);
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct TypedBuilder<A: AlignState = Unaligned> {
+    inner: FormatterBuilder<'static>,
+    _state: PhantomData<A>,
+}
+
+impl TypedBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<A: AlignState> TypedBuilder<A> {
+    fn map(mut self, f: impl FnOnce(&mut FormatterBuilder<'static>)) -> Self {
+        f(&mut self.inner);
+        self
+    }
+
+    /// Like [`FormatterBuilder::with`]
+    #[inline]
+    pub fn with<W, F>(&self, writer: W, f: F) -> fmt::Result
+    where
+        W: Write,
+        F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+    {
+        self.inner.with(writer, f)
+    }
+
+    /// Get the built [`FormatterBuilder`]
+    pub fn build(self) -> FormatterBuilder<'static> {
+        self.inner
+    }
+
+    /// Like [`FormatterBuilder::sign`]
+    pub fn sign(self, sign: impl Into<Option<Sign>>) -> Self {
+        self.map(|b| { b.sign(sign); })
+    }
+
+    /// Like [`FormatterBuilder::sign_aware_zero_pad`]
+    pub fn sign_aware_zero_pad(self, sign_aware_zero_pad: bool) -> Self {
+        self.map(|b| { b.sign_aware_zero_pad(sign_aware_zero_pad); })
+    }
+
+    /// Like [`FormatterBuilder::alternate`]
+    pub fn alternate(self, alternate: bool) -> Self {
+        self.map(|b| { b.alternate(alternate); })
+    }
+
+    /// Like [`FormatterBuilder::width`]
+    pub fn width(self, width: impl Into<Option<u16>>) -> Self {
+        self.map(|b| { b.width(width); })
+    }
+
+    /// Like [`FormatterBuilder::precision`]
+    pub fn precision(self, precision: impl Into<Option<u16>>) -> Self {
+        self.map(|b| { b.precision(precision); })
+    }
+
+    /// Like [`FormatterBuilder::align`], and enable [`fill`](TypedBuilder::fill)
+    pub fn align(self, align: Alignment) -> TypedBuilder<Aligned> {
+        let mut inner = self.inner;
+        inner.align(align);
+        TypedBuilder { inner, _state: PhantomData }
+    }
+
+    /// Like [`FormatterBuilder::fill_align`]
+    pub fn fill_align(
+        self,
+        fill: impl Into<Option<Fill>>,
+        align: Alignment,
+    ) -> TypedBuilder<Aligned> {
+        let mut inner = self.inner;
+        inner.fill_align(fill, align);
+        TypedBuilder { inner, _state: PhantomData }
+    }
+}
+
+impl TypedBuilder<Aligned> {
+    /// Like [`FormatterBuilder::fill`], but never panics
+    pub fn fill(self, fill: impl Into<Option<Fill>>) -> Self {
+        self.map(|b| {
+            if let (Some(fill), Some((fill_char, _))) = (fill.into(), &mut b.fill_align) {
+                *fill_char = Some(fill);
+            }
+        })
+    }
+}
+
+impl<A: AlignState> From<TypedBuilder<A>> for FormatterBuilder<'_> {
+    fn from(value: TypedBuilder<A>) -> Self {
+        value.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Display;
+
+    #[test]
+    fn fill_after_align() {
+        let mut out = String::new();
+        TypedBuilder::new()
+            .width(4)
+            .align(Alignment::Left)
+            .fill(Fill::Zero)
+            .with(&mut out, |f| 7u8.fmt(f))
+            .unwrap();
+        assert_eq!(out, "7000");
+
+        // None keeps the fill, like FormatterBuilder::fill
+        let typed = TypedBuilder::new().align(Alignment::Left).fill(Fill::Zero).fill(None).build();
+        let mut b = FormatterBuilder::new();
+        b.align(Alignment::Left).fill(Fill::Zero).fill(None);
+        assert_eq!(typed.fill_align, b.fill_align);
+        assert_eq!(typed.fill_align, Some((Some(Fill::Zero), Alignment::Left)));
+    }
+
+    #[test]
+    fn realign_keeps_fill() {
+        let b = TypedBuilder::new()
+            .fill_align(Fill::Zero, Alignment::Left)
+            .align(Alignment::Center)
+            .build();
+        b.with(String::new(), |f| {
+            assert_eq!(f.fill(), '0');
+            assert_eq!(f.align(), Some(Alignment::Center));
+            Ok(())
+        }).unwrap();
+    }
+}
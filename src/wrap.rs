@@ -0,0 +1,368 @@
+//! Word wrapping of the closure output
+//!
+//! Words are streamed into a fixed size line buffer, a line is written out
+//! with justification or padding when the next word does not fit
+
+use core::fmt::{self, Formatter, Write};
+
+use crate::{
+    indent::write_spaces,
+    number::{Pad, PadAlign},
+    Alignment, Fill, FormatterBuilder,
+};
+
+/// Capacity of the line buffer in bytes, longer lines are broken earlier
+const LINE: usize = 1024;
+
+/// Options of [`FormatterBuilder::wrap`]
+///
+/// The default is no hanging indent and no justification
+///
+/// # Examples
+///
+/// ```
+/// # use formatter_builder::*;
+/// let options = WrapOptions::new().with_hanging_indent(2).with_justify(true);
+/// assert_eq!(options.hanging_indent(), 2);
+/// assert!(options.justify());
+/// assert_eq!(WrapOptions::default(), WrapOptions::new());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WrapOptions {
+    hanging_indent: u16,
+    justify: bool,
+}
+
+impl WrapOptions {
+    pub const fn new() -> Self {
+        Self { hanging_indent: 0, justify: false }
+    }
+
+    /// Spaces before the wrapped lines of each paragraph, they are in the width
+    pub const fn with_hanging_indent(mut self, hanging_indent: u16) -> Self {
+        self.hanging_indent = hanging_indent;
+        self
+    }
+
+    /// Stretch the spaces between words to the width,
+    /// except the last line of each paragraph
+    pub const fn with_justify(mut self, justify: bool) -> Self {
+        self.justify = justify;
+        self
+    }
+
+    pub fn hanging_indent(&self) -> u16 {
+        self.hanging_indent
+    }
+
+    pub fn justify(&self) -> bool {
+        self.justify
+    }
+}
+
+impl FormatterBuilder<'_> {
+    /// Wrap everything the closure writes into lines of `width` chars, on word boundaries
+    ///
+    /// Each line of the closure output is a paragraph, the whitespaces between words are collapsed.
+    /// A word longer than the width is in a line alone.
+    /// The [`fill`](FormatterBuilder::fill) and [`align`](FormatterBuilder::align) pad each line to the width,
+    /// the closure is formatted without width, fill and align
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use formatter_builder::*;
+    /// let text = "the quick brown fox jumps over the lazy dog";
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().wrap(16, WrapOptions::new().with_hanging_indent(2)).with(&mut writter, |f| {
+    ///     f.write_str(text)
+    /// }).unwrap();
+    /// assert_eq!(writter, "the quick brown\n  fox jumps over\n  the lazy dog");
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().wrap(16, WrapOptions::new().with_justify(true)).with(&mut writter, |f| {
+    ///     f.write_str(text)
+    /// }).unwrap();
+    /// assert_eq!(writter, "the  quick brown\nfox  jumps  over\nthe lazy dog");
+    ///
+    /// let mut writter = String::new();
+    /// FormatterBuilder::new().wrap(16, None).align(Alignment::Center).with(&mut writter, |f| {
+    ///     f.write_str(text)
+    /// }).unwrap();
+    /// assert_eq!(writter, "the quick brown \n fox jumps over \n  the lazy dog  ");
+    /// ```
+    pub fn wrap(&mut self, width: impl Into<Option<u16>>, options: impl Into<Option<WrapOptions>>) -> &mut Self {
+        self.wrap = width.into().map(|width| (width, options.into().unwrap_or_default()));
+        self
+    }
+}
+
+/// [`FormatterBuilder::with`] in [`wrap`](FormatterBuilder::wrap)
+pub(crate) fn with<W, F>(b: &FormatterBuilder<'_>, writer: W, f: F) -> fmt::Result
+where
+    W: Write,
+    F: FnOnce(&mut Formatter<'_>) -> fmt::Result,
+{
+    let (width, options) = b.wrap.unwrap_or_default();
+    let pad = b.fill_align.map(|(fill, align)| Pad {
+        fill: fill.map_or(' ', Fill::as_char),
+        align: match align {
+            Alignment::Left => PadAlign::Left,
+            Alignment::Right => PadAlign::Right,
+            Alignment::Center => PadAlign::Center,
+        },
+        width: 0,
+    });
+    let inner = FormatterBuilder {
+        width: None,
+        fill_align: None,
+        indent: None,
+        wrap: None,
+        ..b.clone()
+    };
+
+    let mut wrap = Wrap {
+        inner: b.indent_writer(writer),
+        width: usize::from(width).saturating_sub(b.indent_width()),
+        options,
+        pad,
+        buf: [0; LINE],
+        len: 0,
+        chars: 0,
+        words: 0,
+        word_start: 0,
+        word_chars: 0,
+        long: false,
+        pending_break: false,
+        continuation: false,
+    };
+    inner.with(&mut wrap as &mut dyn Write, f)?;
+    wrap.end_paragraph()
+}
+
+struct Wrap<W> {
+    inner: W,
+    width: usize,
+    options: WrapOptions,
+    pad: Option<Pad>,
+    /// Words of the line joined by a space, then the current word
+    buf: [u8; LINE],
+    len: usize,
+    /// Chars of the line, without the current word
+    chars: usize,
+    /// Words of the line, without the current word
+    words: usize,
+    word_start: usize,
+    word_chars: usize,
+    /// Current word is too long, written through
+    long: bool,
+    /// A long word is written, the next word is in a new line
+    pending_break: bool,
+    /// Current line is wrapped from the previous line
+    continuation: bool,
+}
+
+impl<W: Write> Wrap<W> {
+    fn line_width(&self) -> usize {
+        match self.continuation {
+            true => self.width.saturating_sub(self.options.hanging_indent.into()),
+            false => self.width,
+        }
+    }
+
+    fn write_hanging(&mut self) -> fmt::Result {
+        match self.continuation {
+            true => write_spaces(&mut self.inner, self.options.hanging_indent.into()),
+            false => Ok(()),
+        }
+    }
+
+    /// Write the line of `buf[..end]`, the last line of the paragraph is not justified
+    fn write_line(&mut self, end: usize, last: bool) -> fmt::Result {
+        self.write_hanging()?;
+        let width = self.line_width();
+        let line = core::str::from_utf8(&self.buf[..end]).unwrap();
+
+        if !last && self.options.justify && self.words > 1 {
+            let gaps = self.words - 1;
+            let extra = width.saturating_sub(self.chars);
+            for (i, word) in line.split(' ').enumerate() {
+                if i != 0 {
+                    let spaces = 1 + extra / gaps + usize::from(i <= extra % gaps);
+                    write_spaces(&mut self.inner, spaces)?;
+                }
+                self.inner.write_str(word)?;
+            }
+        } else {
+            let (pre, post) = self.pad.map_or((0, 0), |pad| Pad { width, ..pad }.split(self.chars));
+            let pad = self.pad.unwrap_or(Pad { fill: ' ', align: PadAlign::Left, width });
+            pad.write_fill(&mut self.inner, pre)?;
+            self.inner.write_str(line)?;
+            pad.write_fill(&mut self.inner, post)?;
+        }
+
+        if !last {
+            self.inner.write_char('\n')?;
+        }
+        Ok(())
+    }
+
+    /// The current word does not fit the line
+    fn overflow(&mut self) -> fmt::Result {
+        if self.words != 0 {
+            self.write_line(self.word_start - 1, false)?;
+            self.buf.copy_within(self.word_start..self.len, 0);
+            self.len -= self.word_start;
+            self.word_start = 0;
+            self.chars = 0;
+            self.words = 0;
+            self.continuation = true;
+        }
+        if self.word_chars > self.line_width() || self.len == LINE {
+            self.write_hanging()?;
+            self.inner.write_str(core::str::from_utf8(&self.buf[..self.len]).unwrap())?;
+            self.len = 0;
+            self.long = true;
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, ch: char) -> fmt::Result {
+        if self.long {
+            return self.inner.write_char(ch);
+        }
+        if self.word_chars == 0 {
+            if self.pending_break {
+                self.pending_break = false;
+                self.continuation = true;
+                self.inner.write_char('\n')?;
+            }
+            if self.words != 0 && self.len + 1 + ch.len_utf8() > LINE {
+                self.write_line(self.len, false)?;
+                self.len = 0;
+                self.chars = 0;
+                self.words = 0;
+                self.continuation = true;
+            }
+            if self.words != 0 {
+                self.buf[self.len] = b' ';
+                self.len += 1;
+            }
+            self.word_start = self.len;
+        }
+
+        if self.len + ch.len_utf8() > LINE {
+            self.overflow()?;
+            if self.long {
+                return self.inner.write_char(ch);
+            }
+        }
+        ch.encode_utf8(&mut self.buf[self.len..]);
+        self.len += ch.len_utf8();
+        self.word_chars += 1;
+
+        let sep = usize::from(self.words != 0);
+        if self.chars + sep + self.word_chars > self.line_width() {
+            self.overflow()?;
+        }
+        Ok(())
+    }
+
+    fn end_word(&mut self) {
+        if self.word_chars == 0 {
+            return;
+        }
+        if self.long {
+            self.long = false;
+            self.pending_break = true;
+        } else {
+            self.chars += usize::from(self.words != 0) + self.word_chars;
+            self.words += 1;
+        }
+        self.word_chars = 0;
+    }
+
+    fn end_paragraph(&mut self) -> fmt::Result {
+        self.end_word();
+        if self.words != 0 {
+            self.write_line(self.len, true)?;
+        }
+        self.len = 0;
+        self.chars = 0;
+        self.words = 0;
+        self.pending_break = false;
+        self.continuation = false;
+        Ok(())
+    }
+}
+
+impl<W: Write> Write for Wrap<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for ch in s.chars() {
+            match ch {
+                '\n' => {
+                    self.end_paragraph()?;
+                    self.inner.write_char('\n')?;
+                },
+                _ if ch.is_whitespace() => self.end_word(),
+                _ => self.push(ch)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Case;
+
+    fn run(b: &FormatterBuilder<'_>, s: &str) -> String {
+        let mut out = String::new();
+        b.with(&mut out, |f| f.write_str(s)).unwrap();
+        out
+    }
+
+    const TEXT: &str = "Lorem  ipsum dolor sit amet,\tconsectetur adipiscing elit,\n\n  sed do eiusmod";
+
+    #[test]
+    fn wrap() {
+        let mut b = FormatterBuilder::new();
+        b.wrap(20, None);
+        assert_eq!(run(&b, TEXT), "\
+Lorem ipsum dolor
+sit amet,
+consectetur
+adipiscing elit,
+
+sed do eiusmod");
+
+        b.wrap(20, WrapOptions::new().with_hanging_indent(4).with_justify(true));
+        assert_eq!(run(&b, TEXT), "\
+Lorem   ipsum  dolor
+    sit        amet,
+    consectetur
+    adipiscing elit,
+
+sed do eiusmod");
+
+        b.wrap(5, WrapOptions::new().with_hanging_indent(2));
+        assert_eq!(run(&b, "a bb abcdefgh c d"), "a bb\n  abcdefgh\n  c d");
+        assert_eq!(run(&b, &"x".repeat(2000)), "x".repeat(2000));
+        // longer than the line buffer
+        b.wrap(2000, None);
+        let line = "xx ".repeat(341);
+        let exp = format!("{0}\n{0}\n{1}", line.trim_end(), "xx ".repeat(318).trim_end());
+        assert_eq!(run(&b, &"xx ".repeat(1000)), exp);
+    }
+
+    #[test]
+    fn with_options() {
+        let mut b = FormatterBuilder::new();
+        b.wrap(8, WrapOptions::new().with_hanging_indent(2)).align(Alignment::Right).fill(Fill::Zero);
+        assert_eq!(run(&b, "ab cd ef gh"), "ab cd ef\n  0000gh");
+
+        b.grouping(',', 3).indent_with("> ").case(Case::Upper).width(100);
+        assert_eq!(run(&b, "1234 x 56789"), "> 01,234\n>   000X\n>   56789");
+    }
+}
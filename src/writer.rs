@@ -0,0 +1,44 @@
+//! Internal [`Write`] adapters for post-processing closure output
+
+use core::fmt::{self, Write};
+
+/// Replace the `+` sign written by core into space, for [`Sign::Space`](crate::Sign::Space)
+///
+/// Only the first `+`, before any char other than the fill, is the sign.
+/// Core writes sign as a standalone `"+"`, so plain text is rarely affected
+pub(crate) struct SignSpace<W> {
+    inner: W,
+    fill: char,
+    started: bool,
+}
+
+impl<W: Write> SignSpace<W> {
+    pub(crate) fn new(inner: W, fill: char) -> Self {
+        Self { inner, fill, started: false }
+    }
+}
+
+impl<W: Write> Write for SignSpace<W> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if !self.started {
+            if s == "+" {
+                self.started = true;
+                return self.inner.write_char(' ');
+            }
+            self.started = s.chars().any(|ch| ch != self.fill);
+        }
+        self.inner.write_str(s)
+    }
+
+    #[inline]
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        if !self.started {
+            self.started = c != self.fill;
+            if c == '+' {
+                return self.inner.write_char(' ');
+            }
+        }
+        self.inner.write_char(c)
+    }
+}